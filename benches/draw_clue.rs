@@ -0,0 +1,55 @@
+use rand::{
+    rngs::StdRng,
+    SeedableRng,
+};
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BatchSize,
+    BenchmarkId,
+    Criterion,
+};
+use serenity::model::{
+    id::UserId,
+    user::User,
+};
+use yeats::game::{
+    bowl::Bowl,
+    clue::Clue,
+    draw_policy::AvoidGuesser,
+    player::Player,
+};
+
+fn player(id: u64, name: &str) -> Player {
+    let user = User { id: UserId(id), name: name.to_string(), bot: false, ..Default::default() };
+    (&user).into()
+}
+
+/// The worst case, where the guesser wrote every clue but the last one
+fn bowl(size: usize, guesser: &Player, other: &Player) -> Bowl {
+    let mut bowl = Bowl::new();
+    (0..size).for_each(|i| {
+        let entered_by = if i + 1 < size { guesser } else { other };
+        bowl.add_clue(&Clue { entered_by: entered_by.clone(), text: format!("clue {}", i) });
+    });
+    bowl
+}
+
+fn draw_clue(c: &mut Criterion) {
+    let performer = player(1, "performer");
+    let guesser = player(2, "guesser");
+    let mut group = c.benchmark_group("draw_clue");
+    for size in [1_000, 10_000, 50_000].iter() {
+        let bowl = bowl(*size, &guesser, &performer);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &bowl, |b, bowl| {
+            b.iter_batched(
+                || bowl.clone(),
+                |mut bowl| bowl.draw_clue(&AvoidGuesser, &performer, &guesser, &[], &mut StdRng::seed_from_u64(0)),
+                BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, draw_clue);
+criterion_main!(benches);
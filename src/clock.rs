@@ -0,0 +1,138 @@
+use std::sync::Mutex;
+use tokio::{
+    sync::oneshot,
+    time::{Duration, Instant, sleep},
+};
+use async_trait::async_trait;
+
+/// Where the bot gets its waiting done. Anything that has to happen after a delay
+/// (the turn timer, redacting the recap, ...) should sleep on the game's clock rather than
+/// calling `tokio::time::sleep` itself, so tests can use a `ManualClock` instead
+#[async_trait]
+pub trait Clock: Send + Sync {
+    async fn sleep(&self, duration: Duration);
+
+    /// Time since some fixed point, only good for telling how long something took
+    fn now(&self) -> Duration;
+}
+
+/// The real thing. Time's told on tokio's clock too, so it can't jump about like the
+/// wall clock can
+pub struct TokioClock {
+    started: Instant,
+}
+
+impl TokioClock {
+    pub fn new() -> TokioClock {
+        TokioClock { started: Instant::now() }
+    }
+}
+
+impl Default for TokioClock {
+    fn default() -> TokioClock {
+        TokioClock::new()
+    }
+}
+
+#[async_trait]
+impl Clock for TokioClock {
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await
+    }
+
+    /// Time since the clock was made
+    fn now(&self) -> Duration {
+        Instant::now() - self.started
+    }
+}
+
+/// A clock that only moves when it's told to with `advance`
+pub struct ManualClock {
+    state: Mutex<ManualState>,
+}
+
+struct ManualState {
+    now: Duration,
+    sleepers: Vec<(Duration, oneshot::Sender<()>)>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock {
+            state: Mutex::new(ManualState { now: Duration::from_secs(0), sleepers: vec![] }),
+        }
+    }
+
+    /// Moves the clock on, waking anything whose sleep is over. Yields either side so
+    /// tasks on the same runtime get to start sleeping first, and run once they're woken
+    pub async fn advance(&self, by: Duration) {
+        tokio::task::yield_now().await;
+        {
+            let mut state = self.state.lock().unwrap();
+            state.now += by;
+            let now = state.now;
+            let (done, sleeping): (Vec<_>, Vec<_>) = state.sleepers
+                .drain(..)
+                .partition(|(until, _)| *until <= now);
+            state.sleepers = sleeping;
+            done.into_iter().for_each(|(_, wake)| { let _ = wake.send(()); });
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> ManualClock {
+        ManualClock::new()
+    }
+}
+
+#[async_trait]
+impl Clock for ManualClock {
+    async fn sleep(&self, duration: Duration) {
+        if duration == Duration::from_secs(0) {
+            return;
+        }
+        let woken = {
+            let mut state = self.state.lock().unwrap();
+            let (wake, woken) = oneshot::channel();
+            let until = state.now + duration;
+            state.sleepers.push((until, wake));
+            woken
+        };
+        let _ = woken.await;
+    }
+
+    /// How long the clock has been going
+    fn now(&self) -> Duration {
+        self.state.lock().unwrap().now
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    #[tokio::test]
+    async fn test_manual_clock_wakes_sleepers_when_due() {
+        let clock = Arc::new(ManualClock::new());
+        let woken = Arc::new(AtomicBool::new(false));
+        let task = {
+            let (clock, woken) = (clock.clone(), woken.clone());
+            tokio::spawn(async move {
+                clock.sleep(Duration::from_secs(5)).await;
+                woken.store(true, Ordering::SeqCst);
+            })
+        };
+        clock.advance(Duration::from_secs(4)).await;
+        assert!(!woken.load(Ordering::SeqCst));
+        clock.advance(Duration::from_secs(1)).await;
+        task.await.unwrap();
+        assert!(woken.load(Ordering::SeqCst));
+        assert_eq!(clock.now(), Duration::from_secs(5));
+    }
+}
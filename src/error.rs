@@ -1,4 +1,7 @@
-use crate::game::game_error::GameError;
+use crate::game::{
+    game_error::GameError,
+    player::Player,
+};
 
 #[derive(Debug, Clone)]
 pub enum Error {
@@ -19,6 +22,33 @@ pub enum Error {
     EmptyBowl,
     BowlNotEmpty,
     NoRound,
+    UnknownCategory(String),
+    BadArguments(String),
+    NotTheHost(Player),
+    NotAPlayer(Player),
+    UnknownSetting(String),
+    BadSetting { key: String, value: String },
+    GuesserMustConfirm,
+    GuesserNotConfirming,
+    PlayerNotAllowedToDispute,
+    NothingToDispute,
+    ClueAlreadyShowing,
+    NotInTextMode,
+    PlayerNotAllowedToGuess,
+    NotRelaying,
+    PlayerNotAllowedToRelay,
+    NoSuchTurn(usize),
+    NoSuchPlayer,
+    PerformingForThemselves,
+    NotReady(Vec<Player>),
+    NoReadyCheck,
+    PlayerNotAllowedToReady,
+    NotSittingOut,
+    NoTurnToSubstitute,
+    AlreadyInTurn(Player),
+    TimesUp,
+    NothingToUndo,
+    NoSuchClue(usize),
 }
 
 impl std::fmt::Display for Error {
@@ -56,7 +86,65 @@ impl std::fmt::Display for Error {
             Error::BowlNotEmpty =>
                 write!(f, "Can't go to the next round yet because the bowl isn't empty, queue up another turn instead with `!next-turn`"),
             Error::NoRound =>
-                write!(f, "Game is not currently in any round, it's either finished or hasn't started yet. To start it, run `!start-game` or `!reset` if you want to play again")
+                write!(f, "Game is not currently in any round, it's either finished or hasn't started yet. To start it, run `!start-game` or `!reset` if you want to play again"),
+            Error::UnknownCategory(c) =>
+                write!(f, "I don't have a word list called \"{}\", try one of celebrities, films, objects or actions", c),
+            Error::BadArguments(usage) =>
+                write!(f, "I couldn't make sense of that, try `{}`", usage),
+            Error::NotTheHost(host) =>
+                write!(f, "Only the host can do that, ask {} nicely", host.name),
+            Error::NotAPlayer(p) =>
+                write!(f, "{} isn't playing", p.name),
+            Error::UnknownSetting(key) =>
+                write!(f, "There's no setting called \"{}\", run `!config` to see them all", key),
+            Error::BadSetting { key, value } =>
+                write!(f, "\"{}\" isn't a valid value for {}", value, key),
+            Error::GuesserMustConfirm =>
+                write!(f, "The guesser has to confirm they got it with `!got-it` before you get the next clue"),
+            Error::GuesserNotConfirming =>
+                write!(f, "Guessers aren't confirming their clues this game, so there's nothing to dispute"),
+            Error::PlayerNotAllowedToDispute =>
+                write!(f, "Only the performer can dispute a clue"),
+            Error::NothingToDispute =>
+                write!(f, "No clues have been solved this turn, so there's nothing to dispute"),
+            Error::ClueAlreadyShowing =>
+                write!(f, "There's already a clue being shown this turn"),
+            Error::NotInTextMode =>
+                write!(f, "This game isn't in text mode, the host can turn it on with `!config text-mode true`"),
+            Error::PlayerNotAllowedToGuess =>
+                write!(f, "Only the guesser can guess"),
+            Error::NotRelaying =>
+                write!(f, "Relay mode is off, the host can turn it on with `!config relay guesser` or `!config relay channel`"),
+            Error::PlayerNotAllowedToRelay =>
+                write!(f, "Only the performer's messages are relayed"),
+            Error::NoSuchTurn(position) =>
+                write!(f, "There's no turn {} in the queue, have a look with `!queue`", position),
+            Error::NoSuchPlayer =>
+                write!(f, "That person isn't playing"),
+            Error::PerformingForThemselves =>
+                write!(f, "Nobody can perform for themselves, pick two different people"),
+            Error::NotReady(players) =>
+                write!(f, "Still waiting on {} to say they're ready with `!ready`",
+                       players.iter()
+                           .map(|p| p.name.clone())
+                           .collect::<Vec<_>>()
+                           .join(" and ")),
+            Error::NoReadyCheck =>
+                write!(f, "There's no ready check this game, just `!start-turn`"),
+            Error::PlayerNotAllowedToReady =>
+                write!(f, "It's not your turn, you don't need to be ready"),
+            Error::NotSittingOut =>
+                write!(f, "You're not sitting out, you're already in the game"),
+            Error::NoTurnToSubstitute =>
+                write!(f, "They don't have a turn coming up to stand in for"),
+            Error::AlreadyInTurn(p) =>
+                write!(f, "{} is already in that turn", p.name),
+            Error::TimesUp =>
+                write!(f, "Time's up, no more clues this turn"),
+            Error::NothingToUndo =>
+                write!(f, "There's nothing left to undo this turn"),
+            Error::NoSuchClue(code) =>
+                write!(f, "There's no clue `{}` to correct, `!clues` will DM you the list", code),
         }
     }
 }
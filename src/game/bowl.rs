@@ -1,18 +1,78 @@
 use rand::{
-    thread_rng,
+    RngCore,
     seq::SliceRandom,
 };
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter, self};
+use std::str::FromStr;
 use crate::game::{
     clue::Clue,
     player::Player,
+    draw_policy::{DrawPolicy, DrawContext},
 };
 
+/// A clue that's been drawn this round, and who it was drawn for
+#[derive(Debug, Clone)]
+pub struct Shown {
+    pub code: usize,
+    pub clue: Clue,
+    pub performer: Player,
+    pub guesser: Player,
+}
+
+/// A clue in the bowl, with the code the host can correct it by. Each clue gets the next
+/// code as it goes in, so the same clue entered twice has two
+pub type CodedClue = (usize, Clue);
+pub type CodedClues = Vec<CodedClue>;
+
+/// What happens to a clue nobody got by the end of a turn, set with `!config put-back <name>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PutBack {
+    /// Back in the bowl and give it a shake
+    Shuffle,
+    /// Back in at the bottom, so it comes out after everything else
+    Bottom,
+    /// Held out of the bowl for the next n draws
+    Cooldown(usize),
+}
+
+impl FromStr for PutBack {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<PutBack, ()> {
+        let mut words = s.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("shuffle"), None) => Ok(PutBack::Shuffle),
+            (Some("bottom"), None) => Ok(PutBack::Bottom),
+            (Some("cooldown"), None) => Ok(PutBack::Cooldown(3)),
+            (Some("cooldown"), Some(n)) => n.parse()
+                .map(PutBack::Cooldown)
+                .map_err(|_| ()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for PutBack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PutBack::Shuffle => write!(f, "shuffle"),
+            PutBack::Bottom => write!(f, "bottom"),
+            PutBack::Cooldown(n) => write!(f, "cooldown {}", n),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Bowl {
-    unsolved: Vec<Clue>,
-    solved: Vec<Clue>,
-    showing: Option<Clue>,
+    unsolved: CodedClues,
+    solved: CodedClues,
+    showing: Option<CodedClue>,
+    shown: Vec<Shown>,
+    /// Put back clues sitting out, with how many more draws until they're back in
+    cooling: Vec<(CodedClue, usize)>,
+    /// How many clues have gone in the bowl, the last code given out
+    num_added: usize,
 }
 
 impl Bowl {
@@ -21,52 +81,158 @@ impl Bowl {
             unsolved: vec![],
             solved: vec![],
             showing: None,
+            shown: vec![],
+            cooling: vec![],
+            num_added: 0,
         }
     }
 
     pub fn showing(&self) -> Option<Clue> {
-        self.showing.clone()
+        self.showing.as_ref().map(|(_, c)| c.clone())
     }
 
     pub fn add_clue(&mut self, c: &Clue) {
-        self.unsolved.append(&mut vec![c.clone()]);
+        self.num_added += 1;
+        self.unsolved.append(&mut vec![(self.num_added, c.clone())]);
     }
 
-    pub fn shuffle(&mut self) {
-        let mut rng = thread_rng();
-        self.unsolved.shuffle(&mut rng);
+    pub fn contains(&self, text: &str) -> bool {
+        self.unsolved
+            .iter()
+            .chain(self.solved.iter())
+            .chain(self.showing.iter())
+            .chain(self.cooling.iter().map(|(c, _)| c))
+            .any(|(_, c)| c.text.eq_ignore_ascii_case(text))
     }
 
-    pub fn draw_clue(&mut self, not_entered_by: &Player) -> Option<Clue> {
-        let (clue, pool) = CluePool::new(self.unsolved.clone()).draw(|c| &c.entered_by == not_entered_by);
-        self.unsolved = pool.to_vec();
+    pub fn shuffle(&mut self, rng: &mut dyn RngCore) {
+        self.unsolved.shuffle(rng);
+    }
+
+    pub fn draw_clue(&mut self, policy: &dyn DrawPolicy, performer: &Player, guesser: &Player, team: &[Player], rng: &mut dyn RngCore) -> Option<Clue> {
+        if self.unsolved.is_empty() {
+            // Better a clue that was only just put back than no clue at all
+            self.unsolved.extend(self.cooling.drain(..).map(|(c, _)| c));
+        }
+        let context = DrawContext { performer, guesser, team, shown: &self.shown };
+        let clue = policy.draw(&mut self.unsolved, &context, rng);
+        self.cool_down();
 
         log::debug!("{:?} being shown", &clue);
+        if let Some((code, c)) = &clue {
+            self.shown.push(Shown { code: *code, clue: c.clone(), performer: performer.clone(), guesser: guesser.clone() });
+        }
         self.showing = clue.clone();
-        clue
+        clue.map(|(_, c)| c)
     }
 
-    pub fn put_back(&mut self) {
-        if let Some(c) = &self.showing {
-            log::debug!("{} marked as unsolved", &c);
-            self.unsolved.push(c.clone());
-            self.showing = None;
-            self.shuffle();
+    pub fn put_back(&mut self, put_back: &PutBack, rng: &mut dyn RngCore) {
+        if let Some(c) = self.showing.take() {
+            log::debug!("{} marked as unsolved", &c.1);
+            match put_back {
+                PutBack::Shuffle => {
+                    self.unsolved.push(c);
+                    self.shuffle(rng);
+                },
+                PutBack::Bottom => self.unsolved.push(c),
+                PutBack::Cooldown(0) => self.unsolved.push(c),
+                PutBack::Cooldown(n) => self.cooling.push((c, *n)),
+            }
         }
     }
 
+    /// Counts down a draw for every cooling clue, any that have sat out long enough go
+    /// back in at the bottom
+    fn cool_down(&mut self) {
+        let (ready, cooling): (Vec<_>, Vec<_>) = self.cooling
+            .drain(..)
+            .map(|(c, n)| (c, n - 1))
+            .partition(|(_, n)| *n == 0);
+        self.unsolved.extend(ready.into_iter().map(|(c, _)| c));
+        self.cooling = cooling;
+    }
+
     pub fn solve_showing_clue(&mut self) {
-        if let Some(c) = &self.showing {
-            log::debug!("{} marked as solved", &c);
-            self.solved.push(c.clone());
-            self.showing = None;
+        if let Some(c) = self.showing.take() {
+            log::debug!("{} marked as solved", &c.1);
+            self.solved.push(c);
         }
     }
 
-    pub fn status(&self) -> String {
+    /// Clues solved so far this round, with their codes
+    pub fn solved(&self) -> CodedClues {
+        self.solved.clone()
+    }
+
+    /// Clues still in the bowl that somebody's already seen this round, with their codes
+    pub fn seen_unsolved(&self) -> CodedClues {
         self.unsolved
+            .iter()
+            .chain(self.cooling.iter().map(|(c, _)| c))
+            .filter(|(code, _)| self.shown.iter().any(|s| s.code == *code))
+            .cloned()
+            .collect()
+    }
+
+    /// Who the clue with this code was last drawn for this round
+    pub fn last_shown(&self, code: usize) -> Option<&Shown> {
+        self.shown
+            .iter()
+            .rev()
+            .find(|s| s.code == code)
+    }
+
+    /// Takes the solved clue with this code out of the solved pile and puts it back in the bowl
+    pub fn unsolve(&mut self, code: usize, rng: &mut dyn RngCore) -> Option<Clue> {
+        let i = self.solved.iter().position(|(c, _)| *c == code)?;
+        let clue = self.solved.remove(i);
+        log::debug!("{} marked as unsolved", &clue.1);
+        self.unsolved.push(clue.clone());
+        self.shuffle(rng);
+        Some(clue.1)
+    }
+
+    /// Takes the clue with this code out of the bowl and counts it as solved
+    pub fn solve(&mut self, code: usize) -> Option<Clue> {
+        let clue = match self.unsolved.iter().position(|(c, _)| *c == code) {
+            Some(i) => self.unsolved.remove(i),
+            None => {
+                let i = self.cooling.iter().position(|((c, _), _)| *c == code)?;
+                self.cooling.remove(i).0
+            },
+        };
+        log::debug!("{} marked as solved", &clue.1);
+        self.solved.push(clue.clone());
+        Some(clue.1)
+    }
+
+    /// Takes a solved clue back out of the solved pile and shows it again, the clue
+    /// that was showing goes back to the top of the bowl
+    pub fn reshow(&mut self, clue: &Clue) {
+        let i = match self.solved.iter().rposition(|(_, c)| c == clue) {
+            Some(i) => i,
+            None => return,
+        };
+        if let Some(c) = self.showing.take() {
+            self.unsolved.insert(0, c);
+        }
+        log::debug!("{} being shown again", clue);
+        self.showing = Some(self.solved.remove(i));
+    }
+
+    pub fn status(&self) -> String {
+        let num_generated = self.unsolved
             .iter()
             .chain(self.solved.iter())
+            .chain(self.cooling.iter().map(|(c, _)| c))
+            .filter(|(_, c)| c.is_generated())
+            .count();
+        let tally = self.unsolved
+            .iter()
+            .chain(self.solved.iter())
+            .chain(self.cooling.iter().map(|(c, _)| c))
+            .map(|(_, c)| c)
+            .filter(|c| !c.is_generated())
             .fold(HashMap::new(), |acc, item| {
                 let mut acc = acc;
                 match acc.get_mut(&item.entered_by.name) {
@@ -77,70 +243,66 @@ impl Bowl {
             })
             .iter()
             .map(|(k, v)| format!("{}: {}", k, v))
-            .collect::<Vec<_>>()
-            .join("\n\t\t")
+            .collect::<Vec<_>>();
+        if num_generated > 0 {
+            tally.into_iter()
+                .chain(vec![format!("Generated by Yeats: {}", num_generated)])
+                .collect::<Vec<_>>()
+                .join("\n\t\t")
+        } else {
+            tally.join("\n\t\t")
+        }
     }
 
     pub fn num_unsolved(&self) -> usize {
-        self.unsolved.len()
+        self.unsolved.len() + self.cooling.len()
     }
 
     pub fn refill(self) -> Bowl {
         let unsolved = self.unsolved
             .into_iter()
             .chain(self.solved.into_iter())
+            .chain(self.showing.into_iter())
+            .chain(self.cooling.into_iter().map(|(c, _)| c))
             .collect();
         Bowl {
             unsolved,
             solved: vec![],
-            showing: None
+            showing: None,
+            shown: vec![],
+            cooling: vec![],
+            num_added: self.num_added,
         }
     }
 }
 
-struct CluePool<C> {
-    pool: Vec<C>,
-    rejected: Vec<C>
-}
-
-impl<C: Clone> CluePool<C> {
-    fn new(pool: Vec<C>) -> CluePool<C> {
-        CluePool { pool, rejected: vec![] }
-    }
-
-    fn to_vec(self) -> Vec<C> {
-        let CluePool { pool, rejected } = self;
-        [pool, rejected].concat().to_vec()
-    }
-
-    fn draw<F>(self, reject_if: F) -> (Option<C>, CluePool<C>) 
-    where
-        F: Fn(&C) -> bool
-    {
-        let CluePool { pool, rejected } = self;
-        match pool.split_first() {
-            Some((head, tail)) => {
-                let pool = tail.to_vec();
-                if reject_if(head) {
-                    let rejected = [rejected, vec![head.clone()]].concat().to_vec();
-                    return CluePool { pool, rejected }.draw(reject_if)
-                } else {
-                    return (Some(head.clone()), CluePool { pool, rejected })
-                }
-            },
-            None => {
-                let pool = vec![];
-                let mut rejected = rejected;
-                let clue = rejected.pop();
-                (clue, CluePool { pool, rejected })
-            }
-        }
+/// Takes the first clue that isn't rejected out of `unsolved`, or the last rejected one if
+/// they all are. Handy for writing a `DrawPolicy`
+///
+/// Whatever was rejected ahead of the drawn clue moves behind the rest, so the next draw
+/// looks at fresh clues first. It's done in place, big bowls don't get cloned
+pub fn draw_rejecting<C, F>(unsolved: &mut Vec<C>, reject_if: F) -> Option<C>
+where
+    F: Fn(&C) -> bool
+{
+    match unsolved.iter().position(|c| !reject_if(c)) {
+        Some(i) => {
+            let clue = unsolved.remove(i);
+            unsolved.rotate_left(i);
+            Some(clue)
+        },
+        None => unsolved.pop(),
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::game::{
+        player::test_player,
+        draw_policy::AvoidGuesser,
+    };
 
     #[test]
     fn test_clue_pool() {
@@ -149,9 +311,10 @@ mod test {
             ("player", "a different clue"),
             ("a different player", "even another clue")
         ].to_vec();
-        let (clue, pool) = CluePool::new(clues).draw(|c| c.0 == "player");
+        let mut pool = clues;
+        let clue = draw_rejecting(&mut pool, |c| c.0 == "player");
         assert_eq!(clue, Some(("a different player", "even another clue")));
-        assert_eq!(pool.to_vec(), [("player", "test clue"), ("player", "a different clue")].to_vec());
+        assert_eq!(pool, [("player", "test clue"), ("player", "a different clue")].to_vec());
     }
     
     #[test]
@@ -160,8 +323,134 @@ mod test {
             ("player", "test clue"),
             ("player", "a different clue"),
         ].to_vec();
-        let (clue, pool) = CluePool::new(clues).draw(|c| c.0 == "player");
+        let mut pool = clues;
+        let clue = draw_rejecting(&mut pool, |c| c.0 == "player");
         assert_eq!(clue, Some(("player", "a different clue")));
-        assert_eq!(pool.to_vec(), [("player", "test clue")].to_vec());
+        assert_eq!(pool, [("player", "test clue")].to_vec());
+    }
+
+    #[test]
+    fn test_clue_pool_rejected_go_behind() {
+        let mut pool = vec![(1, "rejected"), (2, "rejected"), (3, "fine"), (4, "fine"), (5, "rejected")];
+        let clue = draw_rejecting(&mut pool, |c| c.1 == "rejected");
+        assert_eq!(clue, Some((3, "fine")));
+        assert_eq!(pool, vec![(4, "fine"), (5, "rejected"), (1, "rejected"), (2, "rejected")]);
+    }
+
+    #[test]
+    fn test_clue_pool_huge_bowl() {
+        let mut pool = (0..100_000).collect::<Vec<_>>();
+        assert_eq!(draw_rejecting(&mut pool, |_| true), Some(99_999));
+        assert_eq!(draw_rejecting(&mut pool, |c| *c < 99_998), Some(99_998));
+        assert_eq!(pool.len(), 99_998);
+    }
+
+    #[test]
+    fn test_generated_clues_tallied_separately() {
+        let alice = test_player(1, "alice");
+        let mut yeats = test_player(2, "yeats");
+        yeats.user.bot = true;
+        let mut bowl = Bowl::new();
+        bowl.add_clue(&Clue { entered_by: alice.clone(), text: "a clue".to_string() });
+        bowl.add_clue(&Clue { entered_by: yeats.clone(), text: "Jaws".to_string() });
+        bowl.add_clue(&Clue { entered_by: yeats.clone(), text: "Kettle".to_string() });
+        assert_eq!(bowl.status(), "alice: 1\n\t\tGenerated by Yeats: 2");
+        assert!(bowl.contains("jaws"));
+    }
+
+    fn bowl_of(player: &Player, texts: &[&str]) -> Bowl {
+        let mut bowl = Bowl::new();
+        texts.iter()
+            .for_each(|t| bowl.add_clue(&Clue { entered_by: player.clone(), text: t.to_string() }));
+        bowl
+    }
+
+    fn draw(bowl: &mut Bowl, performer: &Player, guesser: &Player) -> Option<String> {
+        bowl.draw_clue(&AvoidGuesser, performer, guesser, &[], &mut StdRng::seed_from_u64(0))
+            .map(|c| c.text)
+    }
+
+    #[test]
+    fn test_put_back_at_the_bottom() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let mut bowl = bowl_of(&alice, &["one", "two", "three"]);
+        draw(&mut bowl, &alice, &bob);
+        bowl.put_back(&PutBack::Bottom, &mut StdRng::seed_from_u64(0));
+        let order = (0..3)
+            .filter_map(|_| draw(&mut bowl, &alice, &bob))
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec!["two", "three", "one"]);
+    }
+
+    #[test]
+    fn test_put_back_cooldown() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let mut bowl = bowl_of(&alice, &["one", "two", "three"]);
+        let failed = draw(&mut bowl, &alice, &bob).unwrap();
+        bowl.put_back(&PutBack::Cooldown(2), &mut StdRng::seed_from_u64(0));
+        assert_eq!(bowl.num_unsolved(), 3);
+        assert!(bowl.contains(&failed));
+        for _ in 0..2 {
+            assert_ne!(draw(&mut bowl, &alice, &bob).as_ref(), Some(&failed));
+            bowl.put_back(&PutBack::Bottom, &mut StdRng::seed_from_u64(0));
+        }
+        let next = (0..3)
+            .filter_map(|_| {
+                let clue = draw(&mut bowl, &alice, &bob);
+                bowl.solve_showing_clue();
+                clue
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(next, vec!["two", "one", "three"]);
+    }
+
+    #[test]
+    fn test_cooling_clue_drawn_rather_than_nothing() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let mut bowl = bowl_of(&alice, &["one"]);
+        draw(&mut bowl, &alice, &bob);
+        bowl.put_back(&PutBack::Cooldown(5), &mut StdRng::seed_from_u64(0));
+        assert_eq!(draw(&mut bowl, &alice, &bob), Some("one".to_string()));
+        assert_eq!(bowl.num_unsolved(), 0);
+    }
+
+    #[test]
+    fn test_the_same_clue_twice_gets_two_codes() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let mut bowl = bowl_of(&alice, &["twice", "once", "twice"]);
+        for _ in 0..3 {
+            draw(&mut bowl, &alice, &bob);
+            bowl.solve_showing_clue();
+        }
+        let mut codes = bowl.solved()
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        assert_eq!(codes, vec![1, 2, 3]);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(bowl.unsolve(3, &mut rng).unwrap().text, "twice");
+        assert_eq!(bowl.unsolve(1, &mut rng).unwrap().text, "twice");
+        assert!(bowl.unsolve(1, &mut rng).is_none());
+        let mut codes = bowl.seen_unsolved()
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        assert_eq!(codes, vec![1, 3]);
+
+        assert_eq!(bowl.solve(3).unwrap().text, "twice");
+        let mut codes = bowl.solved()
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        assert_eq!(codes, vec![2, 3]);
+        assert_eq!(bowl.seen_unsolved()[0].0, 1);
     }
 }
@@ -9,6 +9,13 @@ pub struct Clue {
     pub text: String
 }
 
+impl Clue {
+    /// Clues drawn from the bundled word lists are entered by the bot itself
+    pub fn is_generated(&self) -> bool {
+        self.entered_by.user.bot
+    }
+}
+
 impl Display for Clue {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "```\n{}\n```", self.text)
@@ -0,0 +1,216 @@
+use rand::{
+    Rng,
+    RngCore,
+};
+use std::fmt::{Display, Formatter, self};
+use std::str::FromStr;
+use crate::game::{
+    bowl::{CodedClue, Shown, draw_rejecting},
+    clue::Clue,
+    player::Player,
+};
+
+/// What a `DrawPolicy` gets to know about the turn a clue is being drawn for
+pub struct DrawContext<'a> {
+    pub performer: &'a Player,
+    pub guesser: &'a Player,
+    /// Everyone else guessing, when the whole team guesses
+    pub team: &'a [Player],
+    /// Every clue shown so far this round, and who it was shown to
+    pub shown: &'a [Shown],
+}
+
+/// Picks which clue comes out of the bowl next
+pub trait DrawPolicy: Send + Sync {
+    /// Takes the next clue out of `unsolved`, only returning `None` if it's empty. Any
+    /// randomness should come from `rng`, so a game can be replayed from its seed
+    fn draw(&self, unsolved: &mut Vec<CodedClue>, context: &DrawContext, rng: &mut dyn RngCore) -> Option<CodedClue>;
+}
+
+/// Which `DrawPolicy` the game uses, set with `!config draw <name>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Draw {
+    AvoidGuesser,
+    AvoidPlayers,
+    AvoidSeenByPair,
+    PreferUnseen,
+    Random,
+}
+
+impl Draw {
+    pub fn policy(&self) -> Box<dyn DrawPolicy> {
+        match self {
+            Draw::AvoidGuesser => Box::new(AvoidGuesser),
+            Draw::AvoidPlayers => Box::new(AvoidPlayers),
+            Draw::AvoidSeenByPair => Box::new(AvoidSeenByPair),
+            Draw::PreferUnseen => Box::new(PreferUnseen),
+            Draw::Random => Box::new(Random),
+        }
+    }
+}
+
+impl FromStr for Draw {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Draw, ()> {
+        match s {
+            "avoid-guesser" => Ok(Draw::AvoidGuesser),
+            "avoid-players" => Ok(Draw::AvoidPlayers),
+            "avoid-seen" => Ok(Draw::AvoidSeenByPair),
+            "prefer-unseen" => Ok(Draw::PreferUnseen),
+            "random" => Ok(Draw::Random),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Draw {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Draw::AvoidGuesser => write!(f, "avoid-guesser"),
+            Draw::AvoidPlayers => write!(f, "avoid-players"),
+            Draw::AvoidSeenByPair => write!(f, "avoid-seen"),
+            Draw::PreferUnseen => write!(f, "prefer-unseen"),
+            Draw::Random => write!(f, "random"),
+        }
+    }
+}
+
+/// Clues from the word lists weren't written by anyone playing, so never count
+fn entered_by(clue: &Clue, player: &Player) -> bool {
+    !clue.is_generated() && &clue.entered_by == player
+}
+
+impl DrawContext<'_> {
+    /// Whether one of the people guessing wrote the clue
+    fn guessers_wrote(&self, clue: &Clue) -> bool {
+        entered_by(clue, self.guesser) || self.team.iter().any(|p| entered_by(clue, p))
+    }
+
+    /// Like `draw_rejecting`, but if `reject_if` rules out every clue it falls back to just
+    /// not giving the guessers one of their own
+    fn draw_rejecting<F>(&self, unsolved: &mut Vec<CodedClue>, reject_if: F) -> Option<CodedClue>
+    where
+        F: Fn(&Clue) -> bool
+    {
+        if unsolved.iter().all(|(_, c)| reject_if(c)) {
+            draw_rejecting(unsolved, |(_, c)| self.guessers_wrote(c))
+        } else {
+            draw_rejecting(unsolved, |(_, c)| reject_if(c))
+        }
+    }
+}
+
+/// Nobody has to guess a clue they wrote themselves, if it can be helped
+pub struct AvoidGuesser;
+
+impl DrawPolicy for AvoidGuesser {
+    fn draw(&self, unsolved: &mut Vec<CodedClue>, context: &DrawContext, _rng: &mut dyn RngCore) -> Option<CodedClue> {
+        draw_rejecting(unsolved, |(_, c)| context.guessers_wrote(c))
+    }
+}
+
+/// Nor perform a clue they wrote themselves
+pub struct AvoidPlayers;
+
+impl DrawPolicy for AvoidPlayers {
+    fn draw(&self, unsolved: &mut Vec<CodedClue>, context: &DrawContext, _rng: &mut dyn RngCore) -> Option<CodedClue> {
+        context.draw_rejecting(unsolved, |c| context.guessers_wrote(c) || entered_by(c, context.performer))
+    }
+}
+
+/// As well as avoiding the guesser's clues, a pair doesn't see a clue again in the same
+/// round (say after it was put back at the end of their turn)
+pub struct AvoidSeenByPair;
+
+impl DrawPolicy for AvoidSeenByPair {
+    fn draw(&self, unsolved: &mut Vec<CodedClue>, context: &DrawContext, _rng: &mut dyn RngCore) -> Option<CodedClue> {
+        context.draw_rejecting(unsolved, |c| context.guessers_wrote(c) || context.shown
+            .iter()
+            .any(|s| &s.clue == c && &s.performer == context.performer && &s.guesser == context.guesser))
+    }
+}
+
+/// As well as avoiding the guesser's clues, clues nobody's seen yet this round come first
+pub struct PreferUnseen;
+
+impl DrawPolicy for PreferUnseen {
+    fn draw(&self, unsolved: &mut Vec<CodedClue>, context: &DrawContext, _rng: &mut dyn RngCore) -> Option<CodedClue> {
+        context.draw_rejecting(unsolved, |c| context.guessers_wrote(c) || context.shown
+            .iter()
+            .any(|s| &s.clue == c))
+    }
+}
+
+/// Any clue at all, whoever wrote it
+pub struct Random;
+
+impl DrawPolicy for Random {
+    fn draw(&self, unsolved: &mut Vec<CodedClue>, _context: &DrawContext, rng: &mut dyn RngCore) -> Option<CodedClue> {
+        if unsolved.is_empty() {
+            None
+        } else {
+            let i = rng.gen_range(0..unsolved.len());
+            Some(unsolved.remove(i))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::game::player::test_player;
+
+    /// Codes the clues in the order they're given, as if they'd gone in the bowl that way
+    fn coded(clues: Vec<Clue>) -> Vec<CodedClue> {
+        clues.into_iter()
+            .enumerate()
+            .map(|(i, c)| (i + 1, c))
+            .collect()
+    }
+
+    #[test]
+    fn test_policies() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let carol = test_player(3, "carol");
+        let clue = |p: &Player, text: &str| Clue { entered_by: p.clone(), text: text.to_string() };
+        let unsolved = coded(vec![clue(&bob, "bob's"), clue(&alice, "alice's"), clue(&carol, "carol's")]);
+        let shown = vec![Shown { code: 3, clue: clue(&carol, "carol's"), performer: alice.clone(), guesser: bob.clone() }];
+        let context = DrawContext { performer: &alice, guesser: &bob, team: &[], shown: &shown };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut draw = |policy: &dyn DrawPolicy| policy.draw(&mut unsolved.clone(), &context, &mut rng).unwrap().1.text;
+
+        assert_eq!(draw(&AvoidGuesser), "alice's");
+        assert_eq!(draw(&AvoidPlayers), "carol's");
+        assert_eq!(draw(&AvoidSeenByPair), "alice's");
+        let unseen = coded(vec![clue(&bob, "bob's"), clue(&carol, "carol's"), clue(&alice, "alice's")]);
+        assert_eq!(PreferUnseen.draw(&mut unseen.clone(), &context, &mut rng).unwrap().1.text, "alice's");
+        assert_eq!(AvoidSeenByPair.draw(&mut unseen.clone(), &context, &mut rng).unwrap().1.text, "alice's");
+        assert!(Random.draw(&mut vec![], &context, &mut rng).is_none());
+
+        let team = [carol.clone()];
+        let context = DrawContext { performer: &alice, guesser: &bob, team: &team, shown: &[] };
+        let unsolved = coded(vec![clue(&bob, "bob's"), clue(&carol, "carol's"), clue(&alice, "alice's")]);
+        assert_eq!(AvoidGuesser.draw(&mut unsolved.clone(), &context, &mut rng).unwrap().1.text, "alice's");
+    }
+
+    #[test]
+    fn test_stricter_policies_still_avoid_the_guesser() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let carol = test_player(3, "carol");
+        let clue = |p: &Player, text: &str| Clue { entered_by: p.clone(), text: text.to_string() };
+        let unsolved = coded(vec![clue(&carol, "carol's"), clue(&bob, "bob's")]);
+        let shown = vec![Shown { code: 1, clue: clue(&carol, "carol's"), performer: alice.clone(), guesser: bob.clone() }];
+        let context = DrawContext { performer: &alice, guesser: &bob, team: &[], shown: &shown };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut draw = |policy: &dyn DrawPolicy| policy.draw(&mut unsolved.clone(), &context, &mut rng).unwrap().1.text;
+
+        assert_eq!(draw(&PreferUnseen), "carol's");
+        assert_eq!(draw(&AvoidSeenByPair), "carol's");
+        let unsolved = coded(vec![clue(&alice, "alice's"), clue(&bob, "bob's")]);
+        assert_eq!(AvoidPlayers.draw(&mut unsolved.clone(), &context, &mut rng).unwrap().1.text, "alice's");
+    }
+}
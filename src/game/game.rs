@@ -1,31 +1,76 @@
 use rand::{
-    thread_rng,
+    rngs::StdRng,
+    RngCore,
     seq::SliceRandom,
+    SeedableRng,
+};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::Duration,
 };
 use serenity::{
     prelude::TypeMapKey,
     model::{
         channel::GuildChannel,
+        id::{RoleId, UserId},
     },
     utils::MessageBuilder,
 };
 use crate::{
     error::Error,
+    clock::{Clock, TokioClock},
     game::{
         game_error::GameError,
         player::Player,
         turn::{TurnState, Turn, TurnSummary},
-        bowl::Bowl,
+        bowl::{Bowl, CodedClues},
         clue::Clue,
+        word_list::Category,
+        settings::{Settings, Relay, ReadyCheck},
+        guess,
+        rules::{RoundRule, Foul},
+        pairing::PairingStrategy,
+        draw_policy::DrawPolicy,
+        timer::TurnTimer,
     },
 };
 
+/// Settings that stay put once the game's started
+const PREGAME_SETTINGS: [&str; 3] = ["rounds", "pairing", "seed"];
+
+/// How many clues back `!undo` can go in a turn
+pub const UNDO_HISTORY: usize = 3;
+
 pub struct Game {
     pub players: Vec<Player>,
     pub bowl: Bowl,
     pub state: GameState,
-    pub num_rounds: i64,
-    pub main_channel: Option<GuildChannel>
+    pub settings: Settings,
+    pub main_channel: Option<GuildChannel>,
+    pub host: Option<Player>,
+    pub host_role: Option<RoleId>,
+    pub pairing: Box<dyn PairingStrategy>,
+    pub draw_policy: Box<dyn DrawPolicy>,
+    /// Seeded from `settings.seed`, every shuffle and draw goes through it
+    pub rng: StdRng,
+    /// Set when the bot was started with a seed, which sticks through a reset
+    pub fixed_seed: Option<u64>,
+    pub clock: Arc<dyn Clock>,
+    /// Players who've been skipped too many times and are sitting out until they're `!back`
+    pub inactive: Vec<Player>,
+    /// How many turns in a row each player's had skipped for not starting them
+    pub skips: HashMap<UserId, usize>,
+    /// Timing the current turn, from when it started
+    pub timer: Option<Arc<TurnTimer>>,
+    /// Time's up for the current turn, but the clue that was showing can still be claimed,
+    /// see `Settings::grace`
+    pub in_grace: bool,
+    /// When the showing clue was shown, on `clock`
+    pub clue_shown_at: Option<Duration>,
+    /// Clues solved this turn that can still be undone, the latest last. Only the last
+    /// `UNDO_HISTORY` are kept
+    pub undo_history: Vec<Clue>,
 }
 
 impl TypeMapKey for Game {
@@ -34,23 +79,123 @@ impl TypeMapKey for Game {
 
 impl Game {
     pub fn new() -> Game {
+        let settings = Settings::default();
         Game {
             players: vec![],
             bowl: Bowl::new(),
             state: GameState::PreGame,
-            num_rounds: 3,
-            main_channel: None
+            pairing: settings.pairing.strategy(),
+            draw_policy: settings.draw.policy(),
+            rng: StdRng::seed_from_u64(settings.seed),
+            settings,
+            main_channel: None,
+            host: None,
+            host_role: None,
+            fixed_seed: None,
+            clock: Arc::new(TokioClock::new()),
+            inactive: vec![],
+            skips: HashMap::new(),
+            timer: None,
+            in_grace: false,
+            clue_shown_at: None,
+            undo_history: vec![],
         }
     }
 
+    /// Changes a setting, see `Settings::set`. Some can only be changed before the game starts
+    pub fn configure(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        if PREGAME_SETTINGS.contains(&key) && !matches!(self.state, GameState::PreGame) {
+            return Err(Error::GameAlreadyStarted);
+        }
+        self.settings.set(key, value)?;
+        if key == "pairing" {
+            self.pairing = self.settings.pairing.strategy();
+        }
+        if key == "draw" {
+            self.draw_policy = self.settings.draw.policy();
+        }
+        if key == "seed" {
+            self.rng = StdRng::seed_from_u64(self.settings.seed);
+        }
+        Ok(())
+    }
+
+    /// Anyone with this role can run host commands, as well as the host themselves
+    pub fn with_host_role(self, host_role: Option<RoleId>) -> Game {
+        Game { host_role, ..self }
+    }
+
+    /// Plays every game with this seed rather than a random one, see `Settings::seed`
+    pub fn with_seed(self, fixed_seed: Option<u64>) -> Game {
+        match fixed_seed {
+            Some(seed) => Game {
+                settings: Settings { seed, ..self.settings },
+                rng: StdRng::seed_from_u64(seed),
+                fixed_seed,
+                ..self
+            },
+            None => Game { fixed_seed, ..self },
+        }
+    }
+
+    /// Anything timed in the game sleeps on this clock, see `Clock`
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Game {
+        Game { clock, ..self }
+    }
+
     pub fn reset(&mut self) {
-        *self = Game::new();
+        *self = Game::new()
+            .with_host_role(self.host_role)
+            .with_seed(self.fixed_seed)
+            .with_clock(self.clock.clone());
+    }
+
+    /// Go back to `PreGame` keeping the players, the host and every clue (all put back
+    /// in the bowl), but with fresh settings
+    pub fn rematch(&mut self) {
+        let game = Game::new()
+            .with_host_role(self.host_role)
+            .with_seed(self.fixed_seed)
+            .with_clock(self.clock.clone());
+        *self = Game {
+            players: self.players.clone(),
+            bowl: self.bowl.clone().refill(),
+            host: self.host.clone(),
+            ..game
+        };
+    }
+
+    /// Only the host, or someone with the host role, gets to run the destructive commands.
+    /// If nobody has joined yet there's no host, so anyone can.
+    pub fn check_host(&self, by: &Player, roles: &[RoleId]) -> Result<(), Error> {
+        let has_role = self.host_role
+            .map(|r| roles.contains(&r))
+            .unwrap_or(false);
+        match &self.host {
+            _ if has_role => Ok(()),
+            Some(host) if host != by => Err(Error::NotTheHost(host.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn claim_host(&mut self, p: Player) -> Result<(), Error> {
+        match self.state {
+            GameState::PreGame => {
+                self.host = Some(p);
+                Ok(())
+            },
+            _ => Err(Error::GameAlreadyStarted)
+        }
     }
 
     pub fn status(&self) -> String {
         match &self.state {
             GameState::PreGame => {
-                format!("Game is yet to start, feel free to join or add more clues.\n\tPlayers:\n\t\t{}\n\tClues added:\n\t\t{}", 
+                format!("Game is yet to start, feel free to join or add more clues.\n\tHost: {}\n\tPlayers:\n\t\t{}\n\tClues added:\n\t\t{}", 
+                        self.host
+                            .as_ref()
+                            .map(|p| p.name.clone())
+                            .unwrap_or("nobody yet".to_string()),
                         self.players
                             .iter()
                             .map(|p| p.name.clone())
@@ -63,10 +208,31 @@ impl Game {
                     .as_ref()
                     .map(Turn::status)
                     .unwrap_or("".to_string());
-                format!("We're currently playing round {}. There are {} clues left to be solved. {}", 
+                let teams = self.pairing
+                    .teams()
+                    .map(|teams| teams.iter()
+                         .enumerate()
+                         .map(|(i, team)| format!("\n\tTeam {}: {}", i + 1, team.iter()
+                                                  .map(|p| p.name.clone())
+                                                  .collect::<Vec<_>>()
+                                                  .join(", ")))
+                         .collect::<String>())
+                    .unwrap_or_default();
+                let inactive = if self.inactive.is_empty() {
+                    "".to_string()
+                } else {
+                    format!("\n\tSitting out: {}", self.inactive
+                            .iter()
+                            .map(|p| p.name.clone())
+                            .collect::<Vec<_>>()
+                            .join(", "))
+                };
+                format!("We're currently playing round {}. There are {} clues left to be solved. {}{}{}", 
                         &round.round_number, 
                         self.bowl.num_unsolved(),
-                        turn_status)
+                        turn_status,
+                        teams,
+                        inactive)
                     .trim()
                     .to_string()
             },
@@ -77,6 +243,9 @@ impl Game {
     pub fn add_player(&mut self, p: Player) -> Result<(), Error> {
         match self.state {
             GameState::PreGame => {
+                if self.host.is_none() {
+                    self.host = Some(p.clone());
+                }
                 if !(self.players.contains(&p)) {
                     (*self).players.push(p);
                 }
@@ -86,6 +255,68 @@ impl Game {
         }
     }
 
+    pub fn kick_player(&mut self, p: &Player) -> Result<(), Error> {
+        match self.state {
+            GameState::PreGame => {
+                if !self.players.contains(p) {
+                    return Err(Error::NotAPlayer(p.clone()));
+                }
+                self.players.retain(|q| q != p);
+                if self.host.as_ref() == Some(p) {
+                    self.host = self.players.first().cloned();
+                }
+                Ok(())
+            },
+            _ => Err(Error::GameAlreadyStarted)
+        }
+    }
+
+    /// Everyone who'll be in the next round, leaving out anyone sitting out
+    pub fn active_players(&self) -> Vec<Player> {
+        self.players
+            .iter()
+            .filter(|p| !self.inactive.contains(p))
+            .cloned()
+            .collect()
+    }
+
+    /// Skips the current turn because the performer hasn't started it, moving it to the back
+    /// of the queue. After `Settings::afk_skips` skips in a row the performer sits out, their
+    /// turn is dropped, and they're left out of later rounds until they're `back`. Returns
+    /// whether that happened
+    pub fn skip_turn(&mut self) -> Result<bool, Error> {
+        let turn = self.current_turn()?.clone();
+        // A sub who doesn't turn up shouldn't cost the player their turn, it goes back to them
+        if turn.performer_sub.is_some() {
+            self.update_round(|round| round.skip_turn(true))?;
+            return Ok(false);
+        }
+        let performer = turn.acting_performer().clone();
+        let skips = self.skips.entry(performer.user.id).or_insert(0);
+        *skips += 1;
+        let now_inactive = self.settings.afk_skips > 0 && *skips >= self.settings.afk_skips;
+        self.update_round(|round| round.skip_turn(!now_inactive))?;
+        if now_inactive {
+            // Whoever was performing for them performs for their guesser instead
+            self.update_round(|round| Ok(round.without_guesser(&performer, Some(&turn.guesser))))?;
+        }
+        if now_inactive && !self.inactive.contains(&performer) {
+            log::info!("{} is sitting out", &performer);
+            self.inactive.push(performer);
+        }
+        Ok(now_inactive)
+    }
+
+    /// Someone who was sitting out wants back in, from the next round
+    pub fn back(&mut self, p: &Player) -> Result<(), Error> {
+        if !self.inactive.contains(p) {
+            return Err(Error::NotSittingOut);
+        }
+        self.inactive.retain(|q| q != p);
+        self.skips.remove(&p.user.id);
+        Ok(())
+    }
+
     pub fn add_clue(&mut self, c: &Clue) -> Result<(), Error> {
         match self.state {
             GameState::PreGame => {
@@ -96,6 +327,29 @@ impl Game {
         }
     }
 
+    /// Tops up the bowl with `n` clues from the bundled word lists, entered by `bot`.
+    /// Words already in the bowl are skipped, so fewer than `n` may be added.
+    pub fn fill_bowl(&mut self, n: usize, category: Option<Category>, bot: &Player) -> Result<usize, Error> {
+        match self.state {
+            GameState::PreGame => {
+                let words = category.map(|c| vec![c])
+                    .unwrap_or_else(Category::all)
+                    .iter()
+                    .flat_map(|c| c.words().iter())
+                    .filter(|w| !self.bowl.contains(w))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let clues = words.choose_multiple(&mut self.rng, n)
+                    .map(|w| Clue { entered_by: bot.clone(), text: w.to_string() })
+                    .collect::<Vec<_>>();
+                clues.iter().for_each(|c| self.bowl.add_clue(c));
+                self.bowl.shuffle(&mut self.rng);
+                Ok(clues.len())
+            },
+            _ => Err(Error::GameAlreadyStarted)
+        }
+    }
+
     pub fn advance_game(&mut self) -> Result<(), Error> {
         let new_state = match &self.state {
             GameState::PreGame => {
@@ -103,7 +357,7 @@ impl Game {
                     Err(Error::NoChannel)
                 } else {
                     Ok(GameState::Round(
-                        Round::new(1, &self.players)
+                        Round::new(1, &self.active_players(), &mut *self.pairing, &mut self.rng)
                     ))
                 }
             },
@@ -111,10 +365,10 @@ impl Game {
                 if self.bowl.num_unsolved() > 0 {
                     Err(Error::BowlNotEmpty)
                 } else {
-                    if r.round_number < self.num_rounds {
+                    if r.round_number < self.settings.num_rounds {
                         self.bowl = self.bowl.clone().refill();
                         Ok(GameState::Round(
-                            Round::new(r.round_number + 1, &self.players)
+                            Round::new(r.round_number + 1, &self.active_players(), &mut *self.pairing, &mut self.rng)
                         ))
                     } else {
                         Ok(GameState::End)
@@ -130,7 +384,7 @@ impl Game {
     pub fn start_game(&mut self, channel: GuildChannel) -> Result<(), Error> {
         match &self.state {
             GameState::PreGame => {
-                self.state = GameState::Round(Round::new(1, &self.players));
+                self.state = GameState::Round(Round::new(1, &self.active_players(), &mut *self.pairing, &mut self.rng));
                 self.main_channel = Some(channel);
                 Ok(())
             },
@@ -138,6 +392,67 @@ impl Game {
         }
     }
 
+    fn update_round<F>(&mut self, update: F) -> Result<(), Error>
+    where
+        F: FnOnce(Round) -> Result<Round, Error>
+    {
+        let new_state = match &self.state {
+            GameState::Round(r) => update(r.clone()).map(GameState::Round),
+            GameState::PreGame => Err(Error::GameNotStartedYet),
+            GameState::End => Err(Error::GameFinished),
+        }?;
+        self.state = new_state;
+        Ok(())
+    }
+
+    pub fn find_player(&self, id: UserId) -> Result<Player, Error> {
+        self.players
+            .iter()
+            .find(|p| p.user.id == id)
+            .cloned()
+            .ok_or(Error::NoSuchPlayer)
+    }
+
+    /// The upcoming turns in the order they'll be played
+    pub fn queue(&self) -> Result<Vec<Turn>, Error> {
+        match &self.state {
+            GameState::Round(r) => Ok(r.upcoming()),
+            GameState::PreGame => Err(Error::GameNotStartedYet),
+            GameState::End => Err(Error::GameFinished),
+        }
+    }
+
+    pub fn move_turn(&mut self, from: usize, to: usize) -> Result<(), Error> {
+        self.update_round(|r| r.move_turn(from, to))
+    }
+
+    pub fn swap_turns(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        self.update_round(|r| r.swap_turns(a, b))
+    }
+
+    pub fn set_turn(&mut self, position: usize, performer: UserId, guesser: UserId) -> Result<(), Error> {
+        let performer = self.find_player(performer)?;
+        let guesser = self.find_player(guesser)?;
+        self.update_round(|r| r.set_turn(position, performer, guesser))
+    }
+
+    /// Has `sub` perform or guess instead of `for_player` in their next turn that hasn't
+    /// started, without changing who's paired with whom after that. Anyone can find their
+    /// own sub, otherwise it's up to the host
+    pub fn substitute(&mut self, by: &Player, roles: &[RoleId], sub: UserId, for_player: UserId) -> Result<Turn, Error> {
+        let sub = self.find_player(sub)?;
+        let for_player = self.find_player(for_player)?;
+        if by != &for_player {
+            self.check_host(by, roles)?;
+        }
+        let mut subbed = None;
+        self.update_round(|r| r.substitute(sub, &for_player).map(|(r, t)| {
+            subbed = Some(t);
+            r
+        }))?;
+        subbed.ok_or(Error::NoTurnToSubstitute)
+    }
+
     pub fn prepare_turn(&mut self) -> Result<Turn, Error> {
         if self.bowl.num_unsolved() == 0 {
             return Err(Error::EmptyBowl);
@@ -156,7 +471,83 @@ impl Game {
         })
     }
 
+    /// What auto-pilot should do now the last turn's over. Moves on to the next round if the
+    /// bowl's empty, and gets the next turn ready unless someone already has
+    pub fn auto_pilot_next(&mut self) -> Result<AutoPilot, Error> {
+        if !self.settings.auto_pilot {
+            return Ok(AutoPilot::Off);
+        }
+        let round = match &self.state {
+            GameState::Round(r) => r.clone(),
+            GameState::PreGame => return Ok(AutoPilot::Off),
+            GameState::End => return Ok(AutoPilot::GameOver),
+        };
+        match &round.current_turn {
+            Some(Turn { state: TurnState::Guessing(_), .. }) => Ok(AutoPilot::Off),
+            Some(turn @ Turn { state: TurnState::Ready(_), .. }) =>
+                Ok(AutoPilot::NextTurn { turn: turn.clone(), new_round: None }),
+            _ if self.bowl.num_unsolved() == 0 => {
+                self.advance_game()?;
+                match self.current_round_number() {
+                    Some(round_number) => Ok(AutoPilot::NextTurn {
+                        turn: self.prepare_turn()?,
+                        new_round: Some(round_number),
+                    }),
+                    None => Ok(AutoPilot::GameOver),
+                }
+            },
+            _ => Ok(AutoPilot::NextTurn { turn: self.prepare_turn()?, new_round: None }),
+        }
+    }
+
+    pub fn current_turn(&self) -> Result<&Turn, Error> {
+        match &self.state {
+            GameState::Round(r) => r.current_turn.as_ref().ok_or(Error::NoTurnsQueued),
+            GameState::PreGame => Err(Error::GameNotStartedYet),
+            GameState::End => Err(Error::GameFinished),
+        }
+    }
+
+    /// Who the current turn is waiting on to say they're ready, see `Settings::ready_check`
+    pub fn waiting_on(&self) -> Result<Vec<Player>, Error> {
+        let turn = self.current_turn()?;
+        Ok(turn.waiting_on(self.settings.ready_check.players(turn)))
+    }
+
+    /// `by` says they're ready for the current turn. The turn starts as soon as everyone
+    /// it's waiting on is ready, and is returned just like `start_turn`
+    pub fn ready(&mut self, by: &Player) -> Result<Option<(Turn, i64)>, Error> {
+        if self.settings.ready_check == ReadyCheck::Off {
+            return Err(Error::NoReadyCheck);
+        }
+        let turn = self.current_turn()?.clone();
+        match turn.state {
+            TurnState::Ready(_) => (),
+            TurnState::Guessing(_) => return Err(Error::CurrentTurnNotYetFinished),
+            TurnState::Ended(_) => return Err(Error::CurrentTurnHasEnded),
+        }
+        if !self.settings.ready_check.players(&turn).contains(by) {
+            return Err(Error::PlayerNotAllowedToReady);
+        }
+        self.update_round(|round| Ok(round.with_current_turn(Some(turn.with_ready(by)))))?;
+        if self.waiting_on()?.is_empty() {
+            self.force_start_turn().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Starts the current turn, as long as nobody's still to say they're ready
     pub fn start_turn(&mut self) -> Result<(Turn, i64), Error> {
+        let waiting = self.waiting_on()?;
+        if !waiting.is_empty() {
+            return Err(Error::NotReady(waiting));
+        }
+        self.force_start_turn()
+    }
+
+    /// Starts the current turn whether or not everyone's ready
+    pub fn force_start_turn(&mut self) -> Result<(Turn, i64), Error> {
         let (new_state, turn, round_number) = match &self.state {
             GameState::Round(r) => r.clone()
                 .start_turn()
@@ -164,6 +555,12 @@ impl Game {
             GameState::PreGame => Err(Error::GameNotStartedYet),
             GameState::End => Err(Error::GameFinished)
         }?;
+        self.skips.remove(&turn.acting_performer().user.id);
+        let length = turn.time_left.unwrap_or(self.settings.turn_length);
+        self.timer = Some(Arc::new(TurnTimer::new(self.clock.clone(), length)
+                                   .with_grace(self.settings.grace)));
+        self.in_grace = false;
+        self.undo_history.clear();
         self.state = new_state;
         Ok((turn, round_number))
     }
@@ -174,16 +571,95 @@ impl Game {
             GameState::PreGame => Err(Error::GameNotStartedYet),
             GameState::End => Err(Error::GameFinished)
         }?;
-        self.bowl.put_back();
+        self.bowl.put_back(&self.settings.put_back, &mut self.rng);
+        self.in_grace = false;
+        self.undo_history.clear();
+        // Anything still waiting on the timer, say if the bowl ran out, can stop now
+        if let Some(timer) = self.timer.take() {
+            timer.end_now();
+        }
         self.state = new_state;
         Ok(())
     }
 
+    /// Time's up for this turn, but the showing clue can still be claimed until it's ended
+    pub fn start_grace(&mut self, p: &Player, g: &Player, round_number: i64) -> Result<(), Error> {
+        if !self.is_playing(p, g, round_number) {
+            return Err(Error::TurnDoesntMatchPlayers);
+        }
+        self.in_grace = true;
+        Ok(())
+    }
+
+    /// Whether this turn is still being played, rather than ended or replaced by another
+    pub fn is_playing(&self, p: &Player, g: &Player, round_number: i64) -> bool {
+        match self.current_turn() {
+            Ok(t @ Turn { state: TurnState::Guessing(_), .. }) =>
+                &t.performer == p && &t.guesser == g && self.current_round_number() == Some(round_number),
+            _ => false,
+        }
+    }
+
+    /// Seconds left in the current turn, rounded up
+    pub fn time_left(&self) -> Result<u64, Error> {
+        self.guessing_turn()?;
+        let left = self.timer
+            .as_ref()
+            .map(|t| t.time_left())
+            .unwrap_or_default();
+        Ok(left.as_secs() + u64::from(left.subsec_nanos() > 0))
+    }
+
+    /// The host's giving the current turn `secs` more time, or less if it's negative.
+    /// Returns how long's left now
+    pub fn add_time(&mut self, secs: i64) -> Result<u64, Error> {
+        self.running_timer()?.add_time(secs);
+        self.time_left()
+    }
+
+    /// The host's ending the current turn now, it finishes up just like it would if time
+    /// had run out
+    pub fn end_turn_early(&mut self) -> Result<(), Error> {
+        self.running_timer()?.end_now();
+        Ok(())
+    }
+
+    fn running_timer(&self) -> Result<Arc<TurnTimer>, Error> {
+        self.guessing_turn()?;
+        if self.in_grace {
+            return Err(Error::TimesUp);
+        }
+        self.timer.clone().ok_or(Error::CurrentTurnNotYetStarted)
+    }
+
+    /// The bowl's run out partway through the current turn, so it's over. With `carry_over`
+    /// set, if there's time left and another round to go, the next round starts straight
+    /// away with the same players using up the rest of their time. Returns the ended turn's
+    /// summary, along with the carried over turn if there is one
+    pub fn bowl_emptied(&mut self) -> Result<(TurnSummary, Option<(Turn, i64)>), Error> {
+        let (round, turn, _) = self.guessing_turn()?;
+        let time_left = self.time_left()?;
+        self.end_turn(&turn.performer, &turn.guesser, round.round_number)?;
+        let summary = self.turn_summary()?;
+        if !self.settings.carry_over || time_left == 0 || round.round_number >= self.settings.num_rounds {
+            return Ok((summary, None));
+        }
+        self.advance_game()?;
+        let carried = Turn {
+            state: TurnState::Ready(vec![]),
+            time_left: Some(time_left),
+            ..turn
+        };
+        self.update_round(|r| Ok(r.with_next_turn(carried)))?;
+        self.prepare_turn()?;
+        self.force_start_turn().map(|started| (summary, Some(started)))
+    }
+
     pub fn turn_summary(&self) -> Result<TurnSummary, Error> {
         match &self.state {
             GameState::Round(r) => { // HERE
                 match r.current_turn.clone().ok_or(Error::NoTurnsQueued)?.state {
-                    TurnState::Ready => Err(Error::CurrentTurnNotYetStarted),
+                    TurnState::Ready(_) => Err(Error::CurrentTurnNotYetStarted),
                     TurnState::Guessing(summ) => Ok(summ),
                     TurnState::Ended(summ) => Ok(summ),
                 }
@@ -200,38 +676,287 @@ impl Game {
         }
     }
 
-    pub fn draw_clue(&mut self, by: &Player) -> Result<DrawClue, Error> {
-        let (draw_clue, state) = match &self.state {
+    /// The current round and turn, along with the turn's summary so far, as long as
+    /// the turn is in the middle of guessing
+    fn guessing_turn(&self) -> Result<(Round, Turn, TurnSummary), Error> {
+        match &self.state {
             GameState::Round(round) => {
                 match &round.current_turn {
-                    Some(Turn { performer, guesser, state: TurnState::Guessing(summ) }) =>
-                        if performer == by {
-                            let summ = self.bowl.showing()
-                                .map(|showing| summ.clone().with_clue(showing))
-                                .unwrap_or(summ.clone());
-                            
-                            self.bowl.solve_showing_clue();
-                            let clue = self.bowl.draw_clue(guesser);
-                            Ok((DrawClue {
-                                clue: clue,
-                                performer: performer.clone(),
-                                guesser: guesser.clone(),
-                            }, GameState::Round(round.clone().with_current_turn(round.current_turn.clone().map(|t| t.with_state(TurnState::Guessing(summ)))))))
-                        } else {
-                            Err(Error::PlayerNotAllowedToDrawAClue)
-                        },
-                    Some(Turn { state: TurnState::Ready, .. }) => 
-                        Err(Error::CurrentTurnNotYetStarted),
-                    Some(Turn { state: TurnState::Ended(_), .. }) => 
-                        Err(Error::CurrentTurnHasEnded),
+                    Some(turn) => match &turn.state {
+                        TurnState::Guessing(summ) => Ok((round.clone(), turn.clone(), summ.clone())),
+                        TurnState::Ready(_) => Err(Error::CurrentTurnNotYetStarted),
+                        TurnState::Ended(_) => Err(Error::CurrentTurnHasEnded),
+                    },
                     None => Err(Error::NoTurnsQueued),
                 }
             },
             GameState::PreGame => Err(Error::GameNotStartedYet),
             GameState::End => Err(Error::GameFinished),
-        }?;
-        self.state = state;
-        Ok(draw_clue)
+        }
+    }
+
+    /// Marks the showing clue as solved and draws the next one. Normally only the performer
+    /// can do this, but when `guesser_confirms` is set it's up to the guesser instead.
+    pub fn draw_clue(&mut self, by: &Player) -> Result<DrawClue, Error> {
+        let (round, turn, summ) = self.guessing_turn()?;
+        if self.settings.guesser_confirms {
+            if !turn.is_guessing(by) {
+                return Err(if turn.acting_performer() == by {
+                    Error::GuesserMustConfirm
+                } else {
+                    Error::PlayerNotAllowedToDrawAClue
+                });
+            }
+        } else if turn.acting_performer() != by {
+            return Err(Error::PlayerNotAllowedToDrawAClue);
+        }
+        if self.in_grace && self.bowl.showing().is_none() {
+            return Err(Error::TimesUp);
+        }
+
+        Ok(self.solve_and_draw(round, turn, summ))
+    }
+
+    /// In text mode the guesser types their guesses instead of shouting them. A right guess
+    /// solves the clue and draws the next one, just like `draw_clue`, and a wrong one is ignored
+    pub fn check_guess(&mut self, by: &Player, guess: &str) -> Result<Option<DrawClue>, Error> {
+        if !self.settings.text_mode {
+            return Err(Error::NotInTextMode);
+        }
+        let (round, turn, summ) = self.guessing_turn()?;
+        if !turn.is_guessing(by) {
+            return Err(Error::PlayerNotAllowedToGuess);
+        }
+        match self.bowl.showing() {
+            Some(clue) if guess::is_correct(guess, &clue.text) =>
+                Ok(Some(self.solve_and_draw(round, turn, summ))),
+            _ => Ok(None),
+        }
+    }
+
+    /// In relay mode, where the performer's DMs should be posted so the guesser can see them
+    pub fn relay_target(&self, by: &Player) -> Result<RelayTarget, Error> {
+        let (_, turn, _) = self.guessing_turn()?;
+        if turn.acting_performer() != by {
+            return Err(Error::PlayerNotAllowedToRelay);
+        }
+        match self.settings.relay {
+            Relay::Off => Err(Error::NotRelaying),
+            Relay::Guesser => Ok(RelayTarget::Guessers(turn.guessers())),
+            Relay::Channel => self.main_channel
+                .clone()
+                .map(RelayTarget::Channel)
+                .ok_or(Error::NoChannel),
+        }
+    }
+
+    /// Relays the performer's message, unless `enforce_rules` is set and it breaks this round's
+    /// rule. If `fouls` is set too, breaking the rule puts the clue back and draws another one,
+    /// unless time's up and there's no next clue anyway.
+    pub fn relay(&mut self, by: &Player, text: &str) -> Result<RelayOutcome, Error> {
+        let target = self.relay_target(by)?;
+        let (round, turn, summ) = self.guessing_turn()?;
+        let foul = match (self.settings.enforce_rules, RoundRule::for_round(round.round_number), self.bowl.showing()) {
+            (true, Some(rule), Some(clue)) => rule.check(text, &clue).err(),
+            _ => None,
+        };
+        match foul {
+            Some(foul) if self.settings.fouls && !self.in_grace => {
+                self.bowl.put_back(&self.settings.put_back, &mut self.rng);
+                let clue = self.bowl.draw_clue(&*self.draw_policy, turn.acting_performer(), turn.acting_guesser(), &turn.acting_team(), &mut self.rng);
+                self.clue_shown_at = Some(self.clock.now());
+                self.state = GameState::Round(round.with_current_turn(
+                        Some(turn.clone().with_state(TurnState::Guessing(summ)))));
+                Ok(RelayOutcome::Blocked(foul, Some(DrawClue {
+                    clue,
+                    performer: turn.acting_performer().clone(),
+                    guesser: turn.acting_guesser().clone(),
+                    last_clue: false,
+                })))
+            },
+            Some(foul) => Ok(RelayOutcome::Blocked(foul, None)),
+            None => Ok(RelayOutcome::Relayed(target)),
+        }
+    }
+
+    /// Draws the first clue of a turn, nobody needs to have solved anything yet
+    pub fn draw_first_clue(&mut self) -> Result<DrawClue, Error> {
+        let (round, turn, summ) = self.guessing_turn()?;
+        if self.bowl.showing().is_some() {
+            return Err(Error::ClueAlreadyShowing);
+        }
+        Ok(self.solve_and_draw(round, turn, summ))
+    }
+
+    fn solve_and_draw(&mut self, round: Round, turn: Turn, summ: TurnSummary) -> DrawClue {
+        let summ = match self.bowl.showing() {
+            Some(showing) => {
+                self.undo_history.push(showing.clone());
+                if self.undo_history.len() > UNDO_HISTORY {
+                    self.undo_history.remove(0);
+                }
+                summ.with_clue(showing)
+            },
+            None => summ,
+        };
+        self.bowl.solve_showing_clue();
+        // Once time's up, there's no next clue
+        let clue = if self.in_grace {
+            None
+        } else {
+            self.bowl.draw_clue(&*self.draw_policy, turn.acting_performer(), turn.acting_guesser(), &turn.acting_team(), &mut self.rng)
+        };
+        self.clue_shown_at = Some(self.clock.now());
+        self.state = GameState::Round(round.with_current_turn(
+                Some(turn.clone().with_state(TurnState::Guessing(summ)))));
+        DrawClue {
+            clue,
+            performer: turn.acting_performer().clone(),
+            guesser: turn.acting_guesser().clone(),
+            last_clue: self.in_grace,
+        }
+    }
+
+    /// The clues the host can correct between turns, the ones solved this round and the ones
+    /// still in the bowl that somebody's already seen
+    pub fn correctable_clues(&self) -> Result<(CodedClues, CodedClues), Error> {
+        self.check_between_turns()?;
+        Ok((self.bowl.solved(), self.bowl.seen_unsolved()))
+    }
+
+    /// The host's correcting a clue that was counted as solved but shouldn't have been,
+    /// by its code from `correctable_clues`. It goes back in the bowl, and comes off the last
+    /// turn's recap
+    pub fn mark_unsolved(&mut self, code: usize) -> Result<Clue, Error> {
+        self.check_between_turns()?;
+        let clue = self.bowl
+            .unsolve(code, &mut self.rng)
+            .ok_or(Error::NoSuchClue(code))?;
+        self.update_last_summary(|_, summ| summ.without_clue(&clue))?;
+        Ok(clue)
+    }
+
+    /// The host's correcting a clue that should have been counted as solved, by its code
+    /// from `correctable_clues`. It comes out of the bowl, and if it was last drawn in the
+    /// turn that's just ended it goes on that turn's recap
+    pub fn mark_solved(&mut self, code: usize) -> Result<Clue, Error> {
+        self.check_between_turns()?;
+        let clue = self.bowl
+            .solve(code)
+            .ok_or(Error::NoSuchClue(code))?;
+        let shown = self.bowl.last_shown(code).cloned();
+        self.update_last_summary(|turn, summ| match shown {
+            Some(s) if &s.performer == turn.acting_performer() && &s.guesser == turn.acting_guesser() =>
+                summ.with_clue(clue.clone()),
+            _ => summ,
+        })?;
+        Ok(clue)
+    }
+
+    /// Clues can only be corrected while nobody's guessing
+    fn check_between_turns(&self) -> Result<(), Error> {
+        match &self.state {
+            GameState::Round(r) => match &r.current_turn {
+                Some(Turn { state: TurnState::Guessing(_), .. }) => Err(Error::CurrentTurnNotYetFinished),
+                _ => Ok(()),
+            },
+            GameState::PreGame => Err(Error::GameNotStartedYet),
+            GameState::End => Err(Error::GameFinished),
+        }
+    }
+
+    /// Changes the summary of the turn that's just ended, if it's still the current one
+    fn update_last_summary<F>(&mut self, update: F) -> Result<(), Error>
+    where
+        F: FnOnce(&Turn, TurnSummary) -> TurnSummary
+    {
+        self.update_round(|r| match &r.current_turn {
+            Some(turn @ Turn { state: TurnState::Ended(summ), .. }) => {
+                let turn = turn.clone().with_state(TurnState::Ended(update(turn, summ.clone())));
+                Ok(r.with_current_turn(Some(turn)))
+            },
+            _ => Ok(r),
+        })
+    }
+
+    /// With `clue_limit` set, puts the showing clue back in the bowl if it's been showing for
+    /// too long and draws another. Nothing happens if it hasn't, or if time's up
+    pub fn return_stale_clue(&mut self) -> Result<Option<DrawClue>, Error> {
+        let limit = Duration::from_secs(self.settings.clue_limit);
+        if limit.as_secs() == 0 || self.in_grace {
+            return Ok(None);
+        }
+        let (round, turn, summ) = self.guessing_turn()?;
+        let now = self.clock.now();
+        let stale = self.clue_shown_at
+            .map(|at| now.saturating_sub(at) >= limit)
+            .unwrap_or(false);
+        let showing = match self.bowl.showing() {
+            Some(clue) if stale => clue,
+            _ => return Ok(None),
+        };
+        log::info!("{} ran out of time", &showing);
+        self.bowl.put_back(&self.settings.put_back, &mut self.rng);
+        let clue = self.bowl.draw_clue(&*self.draw_policy, turn.acting_performer(), turn.acting_guesser(), &turn.acting_team(), &mut self.rng);
+        self.clue_shown_at = Some(now);
+        self.state = GameState::Round(round.with_current_turn(
+                Some(turn.clone().with_state(TurnState::Guessing(summ.with_timed_out(showing))))));
+        Ok(Some(DrawClue {
+            clue,
+            performer: turn.acting_performer().clone(),
+            guesser: turn.acting_guesser().clone(),
+            last_clue: false,
+        }))
+    }
+
+    /// When `guesser_confirms` is set, the performer can dispute the last clue the guesser
+    /// claimed. It's taken off the turn's solved clues and shown to the performer again.
+    pub fn dispute_clue(&mut self, by: &Player) -> Result<DrawClue, Error> {
+        if !self.settings.guesser_confirms {
+            return Err(Error::GuesserNotConfirming);
+        }
+        let (round, turn, summ) = self.guessing_turn()?;
+        if turn.acting_performer() != by {
+            return Err(Error::PlayerNotAllowedToDispute);
+        }
+        // It'd be shown again, and could be claimed again, after time's up
+        if self.in_grace {
+            return Err(Error::TimesUp);
+        }
+        self.unsolve_last_clue(round, turn, summ).ok_or(Error::NothingToDispute)
+    }
+
+    /// The performer (or the host) takes back the last clue they said was solved, say if
+    /// they sent `y` by mistake. It's shown to them again, and they can go back as far as
+    /// `UNDO_HISTORY` clues in a turn
+    pub fn undo(&mut self, by: &Player, roles: &[RoleId]) -> Result<DrawClue, Error> {
+        let (round, turn, summ) = self.guessing_turn()?;
+        if turn.acting_performer() != by {
+            self.check_host(by, roles)?;
+        }
+        if self.undo_history.is_empty() {
+            return Err(Error::NothingToUndo);
+        }
+        self.unsolve_last_clue(round, turn, summ).ok_or(Error::NothingToUndo)
+    }
+
+    /// Takes the last solved clue off the turn's summary and shows it again
+    fn unsolve_last_clue(&mut self, round: Round, turn: Turn, summ: TurnSummary) -> Option<DrawClue> {
+        let (clue, summ) = summ.without_last_clue();
+        let clue = clue?;
+        if self.undo_history.last() == Some(&clue) {
+            self.undo_history.pop();
+        }
+        self.bowl.reshow(&clue);
+        self.clue_shown_at = Some(self.clock.now());
+        self.state = GameState::Round(round.with_current_turn(
+                Some(turn.clone().with_state(TurnState::Guessing(summ)))));
+        Some(DrawClue {
+            clue: Some(clue),
+            performer: turn.acting_performer().clone(),
+            guesser: turn.acting_guesser().clone(),
+            last_clue: false,
+        })
     }
 }
 
@@ -247,6 +972,30 @@ pub struct DrawClue {
     pub clue: Option<Clue>,
     pub performer: Player,
     pub guesser: Player,
+    /// The clue solved was claimed after time was up, so there's no next one
+    pub last_clue: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum AutoPilot {
+    /// Auto-pilot's off, the game isn't going, or someone's mid-turn
+    Off,
+    /// This turn starts after the countdown. `new_round` is set when the round just moved on
+    NextTurn { turn: Turn, new_round: Option<i64> },
+    GameOver,
+}
+
+#[derive(Debug, Clone)]
+pub enum RelayTarget {
+    Guessers(Vec<Player>),
+    Channel(GuildChannel),
+}
+
+/// A blocked message comes with the performer's next clue if it counted as a foul
+#[derive(Debug, Clone)]
+pub enum RelayOutcome {
+    Relayed(RelayTarget),
+    Blocked(Foul, Option<DrawClue>),
 }
 
 #[derive(Debug, Clone)]
@@ -257,13 +1006,11 @@ pub struct Round {
 }
 
 impl Round {
-    pub fn new(round_number: i64, players: &Vec<Player>) -> Round {
-        let mut players = players.clone();
-        let mut rng = thread_rng();
-        players.shuffle(&mut rng);
-        let turn_queue = players.iter()
-            .zip(players.iter().cycle().skip(1))
-            .map(|(p1, p2)| Turn::new(p1.clone(), p2.clone()))
+    pub fn new(round_number: i64, players: &Vec<Player>, pairing: &mut dyn PairingStrategy, rng: &mut dyn RngCore) -> Round {
+        // Turns are popped off the end of the queue
+        let turn_queue = pairing.turns(round_number, players, rng)
+            .into_iter()
+            .rev()
             .collect();
         Round { round_number, turn_queue, current_turn: None }
     }
@@ -284,10 +1031,9 @@ impl Round {
                     current_turn: Some(current_turn)
                 })
             },
-            Some(Turn { performer, guesser, state: TurnState::Ended(_) }) => {
-                let ended_turn = Turn::new(performer.clone(), guesser.clone());
+            Some(Turn { state: TurnState::Ended(_), .. }) => {
+                // The ended turn was already put to the back of the queue by `end_turn`
                 let mut turn_queue = self.turn_queue;
-                turn_queue.insert(0, ended_turn);
                 let current_turn = turn_queue.pop()
                     .ok_or(Error::EmptyTurnQueue)?;
                 Ok(Round {
@@ -296,7 +1042,7 @@ impl Round {
                     current_turn: Some(current_turn)
                 })
             },
-            Some(Turn { state: TurnState::Ready, .. }) => {
+            Some(Turn { state: TurnState::Ready(_), .. }) => {
                 Err(Error::CurrentTurnNotYetFinished)
             },
             Some(Turn { state: TurnState::Guessing(_), .. }) => {
@@ -305,18 +1051,132 @@ impl Round {
         }
     }
 
+    /// Puts a turn that hasn't started yet to the back of the queue, or drops it
+    pub fn skip_turn(self, requeue: bool) -> Result<Round, Error> {
+        match self.current_turn {
+            Some(t @ Turn { state: TurnState::Ready(_), .. }) => {
+                let mut turn_queue = self.turn_queue;
+                if requeue {
+                    turn_queue.insert(0, t.requeued());
+                }
+                Ok(Round { turn_queue, current_turn: None, ..self })
+            },
+            Some(_) => Err(Error::CurrentTurnNotYetFinished),
+            None => Err(Error::NoTurnsQueued),
+        }
+    }
+
+    /// Takes `p` out of the queued turns they're guessing in, see `Turn::without_guesser`.
+    /// Turns left with nobody to guess are dropped
+    pub fn without_guesser(self, p: &Player, replacement: Option<&Player>) -> Round {
+        let turn_queue = self.turn_queue
+            .into_iter()
+            .filter_map(|t| t.without_guesser(p, replacement))
+            .collect();
+        Round { turn_queue, ..self }
+    }
+
+    /// The queued turns in the order they'll be played
+    pub fn upcoming(&self) -> Vec<Turn> {
+        self.turn_queue
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+
+    fn with_upcoming(self, upcoming: Vec<Turn>) -> Round {
+        Round {
+            turn_queue: upcoming.into_iter().rev().collect(),
+            ..self
+        }
+    }
+
+    /// Positions count from 1, the next turn to be played
+    fn check_position(&self, position: usize) -> Result<usize, Error> {
+        if position >= 1 && position <= self.turn_queue.len() {
+            Ok(position - 1)
+        } else {
+            Err(Error::NoSuchTurn(position))
+        }
+    }
+
+    pub fn move_turn(self, from: usize, to: usize) -> Result<Round, Error> {
+        let from = self.check_position(from)?;
+        let to = self.check_position(to)?;
+        let mut upcoming = self.upcoming();
+        let turn = upcoming.remove(from);
+        upcoming.insert(to, turn);
+        Ok(self.with_upcoming(upcoming))
+    }
+
+    pub fn swap_turns(self, a: usize, b: usize) -> Result<Round, Error> {
+        let a = self.check_position(a)?;
+        let b = self.check_position(b)?;
+        let mut upcoming = self.upcoming();
+        upcoming.swap(a, b);
+        Ok(self.with_upcoming(upcoming))
+    }
+
+    pub fn set_turn(self, position: usize, performer: Player, guesser: Player) -> Result<Round, Error> {
+        let position = self.check_position(position)?;
+        if performer == guesser {
+            return Err(Error::PerformingForThemselves);
+        }
+        let mut upcoming = self.upcoming();
+        upcoming[position] = Turn::new(performer, guesser);
+        Ok(self.with_upcoming(upcoming))
+    }
+
+    /// Puts `turn` at the front of the queue
+    pub fn with_next_turn(self, turn: Turn) -> Round {
+        let mut turn_queue = self.turn_queue;
+        turn_queue.push(turn);
+        Round { turn_queue, ..self }
+    }
+
+    /// Puts `sub` in for `for_player` in the first turn they're in that hasn't started yet,
+    /// the current one if it's still getting ready, otherwise the next one in the queue
+    pub fn substitute(self, sub: Player, for_player: &Player) -> Result<(Round, Turn), Error> {
+        let subbed = |t: &Turn| -> Result<Turn, Error> {
+            if t.involves(&sub) {
+                return Err(Error::AlreadyInTurn(sub.clone()));
+            }
+            t.clone().with_substitute(sub.clone(), for_player).ok_or(Error::NoTurnToSubstitute)
+        };
+        match &self.current_turn {
+            Some(t @ Turn { state: TurnState::Ready(_), .. }) if t.involves(for_player) => {
+                let t = subbed(t)?;
+                Ok((Round { current_turn: Some(t.clone()), ..self }, t))
+            },
+            _ => {
+                let mut upcoming = self.upcoming();
+                let i = upcoming.iter()
+                    .position(|t| t.involves(for_player))
+                    .ok_or(Error::NoTurnToSubstitute)?;
+                upcoming[i] = subbed(&upcoming[i])?;
+                let t = upcoming[i].clone();
+                Ok((self.with_upcoming(upcoming), t))
+            },
+        }
+    }
+
     pub fn ready_turn_message(&self) -> Result<String, GameError> {
         match &self.current_turn {
             Some(t) => match t.state {
-                TurnState::Ready => Ok(
-                    MessageBuilder::new()
-                        .push("Get ready ")
-                        .mention(&t.performer.user)
-                        .push(", you'll be performing for ")
-                        .mention(&t.guesser.user)
-                        .push("!!")
-                        .build()
-                ),
+                TurnState::Ready(_) => {
+                    let mut message = MessageBuilder::new();
+                    message.push("Get ready ")
+                        .mention(&t.acting_performer().user)
+                        .push(", you'll be performing for ");
+                    for (i, g) in t.guessers().iter().enumerate() {
+                        if i > 0 {
+                            message.push(", ");
+                        }
+                        message.mention(&g.user);
+                    }
+                    Ok(message.push("!!").build())
+                },
                 TurnState::Guessing(_) => Err(GameError::BadTurnState(t.clone())),
                 TurnState::Ended(_) => Err(GameError::BadTurnState(t.clone())),
             },
@@ -327,7 +1187,7 @@ impl Round {
     pub fn start_turn(self) -> Result<(Round, Turn), Error> {
         match self.current_turn {
             Some(t) => match t.state {
-                TurnState::Ready => Ok((Round {
+                TurnState::Ready(_) => Ok((Round {
                     round_number: self.round_number,
                     turn_queue: self.turn_queue,
                     current_turn: Some(t.clone().as_guessing())
@@ -344,12 +1204,18 @@ impl Round {
             Some(t) => {
                 if (p == &t.performer) & (g == &t.guesser) & (self.round_number == round_number) {
                     match t.state {
-                        TurnState::Guessing(_) => Ok(Round {
-                            round_number: self.round_number,
-                            turn_queue: self.turn_queue,
-                            current_turn: Some(t.as_ended())
-                        }),
-                        TurnState::Ready => Err(Error::CurrentTurnNotYetStarted),
+                        TurnState::Guessing(_) => {
+                            let mut turn_queue = self.turn_queue;
+                            if t.time_left.is_none() {
+                                turn_queue.insert(0, t.requeued());
+                            }
+                            Ok(Round {
+                                round_number: self.round_number,
+                                turn_queue,
+                                current_turn: Some(t.as_ended())
+                            })
+                        },
+                        TurnState::Ready(_) => Err(Error::CurrentTurnNotYetStarted),
                         TurnState::Ended(_) => Err(Error::CurrentTurnHasEnded),
                     }
                 } else {
@@ -360,3 +1226,560 @@ impl Round {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::test_player;
+    use crate::game::turn::Substitute;
+    use crate::clock::ManualClock;
+
+    fn start_round(game: &mut Game) {
+        game.state = GameState::Round(Round::new(1, &game.players, &mut *game.pairing, &mut game.rng));
+    }
+
+    /// Alice and Bob's game with these clues in the bowl, with the first turn ready to start
+    fn ready_to_play(game: Game, clues: &[(&Player, &str)]) -> (Game, Turn) {
+        let mut game = game;
+        game.add_player(test_player(1, "alice")).unwrap();
+        game.add_player(test_player(2, "bob")).unwrap();
+        for (p, text) in clues {
+            game.add_clue(&Clue { entered_by: (*p).clone(), text: text.to_string() }).unwrap();
+        }
+        start_round(&mut game);
+        let turn = game.prepare_turn().unwrap();
+        (game, turn)
+    }
+
+    /// The same, with the first turn started and its first clue showing
+    fn playing(game: Game, clues: &[(&Player, &str)]) -> (Game, Turn) {
+        let (mut game, turn) = ready_to_play(game, clues);
+        game.start_turn().unwrap();
+        game.draw_first_clue().unwrap();
+        (game, turn)
+    }
+
+    #[test]
+    fn test_only_host_passes_host_check() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let mut game = Game::new().with_host_role(Some(RoleId(7)));
+        assert!(game.check_host(&bob, &[]).is_ok());
+        game.add_player(alice.clone()).unwrap();
+        game.add_player(bob.clone()).unwrap();
+        assert!(game.check_host(&alice, &[]).is_ok());
+        assert!(game.check_host(&bob, &[]).is_err());
+        assert!(game.check_host(&bob, &[RoleId(7)]).is_ok());
+        game.claim_host(bob.clone()).unwrap();
+        assert!(game.check_host(&alice, &[]).is_err());
+        game.reset();
+        assert_eq!(game.host_role, Some(RoleId(7)));
+    }
+
+    #[test]
+    fn test_rematch_keeps_players_and_clues() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let (mut game, _) = playing(Game::new(), &[(&alice, "a clue"), (&bob, "another clue")]);
+        game.settings.num_rounds = 1;
+        game.rematch();
+        assert!(matches!(game.state, GameState::PreGame));
+        assert_eq!(game.players, vec![alice.clone(), bob]);
+        assert_eq!(game.host, Some(alice));
+        assert_eq!(game.bowl.num_unsolved(), 2);
+        assert_eq!(game.settings.num_rounds, 3);
+    }
+
+    #[test]
+    fn test_settings_are_checked() {
+        let mut game = Game::new();
+        game.add_player(test_player(1, "alice")).unwrap();
+        game.add_player(test_player(2, "bob")).unwrap();
+        assert!(matches!(game.configure("rounds", "0"), Err(Error::BadSetting { .. })));
+        assert!(matches!(game.configure("turn-length", "10"), Err(Error::BadSetting { .. })));
+        game.configure("turn-length", "11").unwrap();
+        game.configure("rounds", "1").unwrap();
+        start_round(&mut game);
+        assert!(matches!(game.configure("rounds", "2"), Err(Error::GameAlreadyStarted)));
+        assert!(matches!(game.configure("pairing", "fixed"), Err(Error::GameAlreadyStarted)));
+        assert!(matches!(game.configure("seed", "1"), Err(Error::GameAlreadyStarted)));
+        game.configure("turn-length", "30").unwrap();
+        assert_eq!((game.settings.num_rounds, game.settings.turn_length), (1, 30));
+    }
+
+    #[test]
+    fn test_guesser_confirms_and_performer_disputes() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let (mut game, Turn { performer, guesser, .. }) = playing(Game::new(), &[(&alice, "a clue"), (&bob, "another clue")]);
+        game.settings.guesser_confirms = true;
+        let first = game.bowl.showing().unwrap();
+
+        assert!(matches!(game.draw_clue(&performer), Err(Error::GuesserMustConfirm)));
+        assert!(matches!(game.dispute_clue(&performer), Err(Error::NothingToDispute)));
+        game.draw_clue(&guesser).unwrap();
+        assert!(matches!(game.dispute_clue(&guesser), Err(Error::PlayerNotAllowedToDispute)));
+
+        let disputed = game.dispute_clue(&performer).unwrap().clue;
+        assert_eq!(disputed, Some(first.clone()));
+        assert_eq!(game.bowl.showing(), Some(first));
+        assert_eq!(game.bowl.num_unsolved(), 1);
+    }
+
+    #[test]
+    fn test_check_guess() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let (mut game, Turn { performer, guesser, .. }) = playing(Game::new(), &[(&alice, "Beyoncé"), (&bob, "Beyoncé")]);
+        game.settings.text_mode = true;
+
+        assert!(matches!(game.check_guess(&performer, "beyonce"), Err(Error::PlayerNotAllowedToGuess)));
+        assert!(matches!(game.check_guess(&guesser, "rihanna"), Ok(None)));
+        assert_eq!(game.bowl.num_unsolved(), 1);
+        let draw = game.check_guess(&guesser, "beyonse").unwrap().unwrap();
+        assert!(draw.clue.is_some());
+        assert_eq!(game.turn_summary().unwrap().to_string(), "Clues solved:\n```\nBeyoncé\n```");
+    }
+
+    #[test]
+    fn test_relay_target() {
+        let alice = test_player(1, "alice");
+        let (mut game, Turn { performer, guesser, .. }) = ready_to_play(Game::new(), &[(&alice, "a clue")]);
+        assert!(matches!(game.relay_target(&performer), Err(Error::CurrentTurnNotYetStarted)));
+        game.start_turn().unwrap();
+
+        assert!(matches!(game.relay_target(&performer), Err(Error::NotRelaying)));
+        game.settings.relay = Relay::Guesser;
+        assert!(matches!(game.relay_target(&guesser), Err(Error::PlayerNotAllowedToRelay)));
+        assert!(matches!(game.relay_target(&performer), Ok(RelayTarget::Guessers(p)) if p == vec![guesser.clone()]));
+        game.settings.relay = Relay::Channel;
+        assert!(matches!(game.relay_target(&performer), Err(Error::NoChannel)));
+    }
+
+    #[test]
+    fn test_relay_blocks_fouls() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let (mut game, Turn { performer, .. }) = playing(Game::new(), &[(&alice, "Lighthouse"), (&bob, "Lighthouse")]);
+        game.settings.relay = Relay::Guesser;
+
+        assert!(matches!(game.relay(&performer, "a light house"), Ok(RelayOutcome::Relayed(_))));
+        game.settings.enforce_rules = true;
+        assert!(matches!(game.relay(&performer, "boats crash on the rocks"), Ok(RelayOutcome::Relayed(_))));
+        assert!(matches!(game.relay(&performer, "lighthouses"), Ok(RelayOutcome::Blocked(Foul::SaidTheClue(_), None))));
+        game.settings.fouls = true;
+        let outcome = game.relay(&performer, "a lighthouse");
+        assert!(matches!(outcome, Ok(RelayOutcome::Blocked(Foul::SaidTheClue(_), Some(DrawClue { clue: Some(_), .. })))));
+        assert_eq!(game.bowl.num_unsolved(), 1);
+    }
+
+    #[test]
+    fn test_queue_reordering() {
+        let players = (1..=4)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut game = Game::new();
+        players.iter().for_each(|p| game.add_player(p.clone()).unwrap());
+        game.add_clue(&Clue { entered_by: players[0].clone(), text: "a clue".to_string() }).unwrap();
+        start_round(&mut game);
+        let queue = game.queue().unwrap();
+
+        game.move_turn(4, 1).unwrap();
+        game.swap_turns(2, 3).unwrap();
+        assert!(matches!(game.swap_turns(2, 5), Err(Error::NoSuchTurn(5))));
+        assert!(matches!(game.set_turn(4, players[0].user.id, players[0].user.id),
+                         Err(Error::PerformingForThemselves)));
+        game.set_turn(4, players[0].user.id, players[1].user.id).unwrap();
+        let expected = vec![&queue[3], &queue[1], &queue[0]]
+            .into_iter()
+            .map(|t| (t.performer.clone(), t.guesser.clone()))
+            .chain(vec![(players[0].clone(), players[1].clone())])
+            .collect::<Vec<_>>();
+        let reordered = game.queue()
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.performer, t.guesser))
+            .collect::<Vec<_>>();
+        assert_eq!(reordered, expected);
+
+        let turn = game.prepare_turn().unwrap();
+        assert_eq!((turn.performer, turn.guesser), expected[0]);
+        game.start_turn().unwrap();
+        game.end_turn(&expected[0].0, &expected[0].1, 1).unwrap();
+        let requeued = game.queue().unwrap().pop().unwrap();
+        assert_eq!((requeued.performer, requeued.guesser), expected[0]);
+        assert_eq!(game.prepare_turn().unwrap().performer, expected[1].0);
+    }
+
+    #[test]
+    fn test_same_seed_plays_the_same() {
+        let play = |seed: &str| {
+            let mut yeats = test_player(99, "yeats");
+            yeats.user.bot = true;
+            let mut game = Game::new();
+            game.configure("seed", seed).unwrap();
+            game.configure("draw", "random").unwrap();
+            (1..=4).for_each(|i| game.add_player(test_player(i, &i.to_string())).unwrap());
+            game.fill_bowl(20, None, &yeats).unwrap();
+            start_round(&mut game);
+            let queue = game.queue()
+                .unwrap()
+                .into_iter()
+                .map(|t| (t.performer.name, t.guesser.name))
+                .collect::<Vec<_>>();
+            let performer = game.prepare_turn().unwrap().performer;
+            game.start_turn().unwrap();
+            let first = game.draw_first_clue().unwrap();
+            let draws = vec![first]
+                .into_iter()
+                .chain((0..4).map(|_| game.draw_clue(&performer).unwrap()))
+                .map(|d| d.clue.unwrap().text)
+                .collect::<Vec<_>>();
+            (queue, draws)
+        };
+        assert_eq!(play("42"), play("42"));
+    }
+
+    #[test]
+    fn test_auto_pilot_runs_through_the_rounds() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let mut game = Game::new();
+        game.add_player(alice.clone()).unwrap();
+        game.add_player(bob.clone()).unwrap();
+        game.add_clue(&Clue { entered_by: alice.clone(), text: "a clue".to_string() }).unwrap();
+        game.settings.num_rounds = 2;
+        start_round(&mut game);
+        assert!(matches!(game.auto_pilot_next(), Ok(AutoPilot::Off)));
+        game.configure("auto-pilot", "true").unwrap();
+
+        let play = |game: &mut Game, turn: Turn| {
+            let (_, round_number) = game.start_turn().unwrap();
+            assert!(matches!(game.auto_pilot_next(), Ok(AutoPilot::Off)));
+            game.draw_first_clue().unwrap();
+            game.draw_clue(&turn.performer).unwrap();
+            game.end_turn(&turn.performer, &turn.guesser, round_number).unwrap();
+        };
+        let turn = match game.auto_pilot_next() {
+            Ok(AutoPilot::NextTurn { turn, new_round: None }) => turn,
+            other => panic!("{:?}", other),
+        };
+        play(&mut game, turn);
+        let turn = match game.auto_pilot_next() {
+            Ok(AutoPilot::NextTurn { turn, new_round: Some(2) }) => turn,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(game.bowl.num_unsolved(), 1);
+        play(&mut game, turn);
+        assert!(matches!(game.auto_pilot_next(), Ok(AutoPilot::GameOver)));
+        assert!(matches!(game.state, GameState::End));
+    }
+
+    #[test]
+    fn test_ready_check() {
+        let alice = test_player(1, "alice");
+        let carol = test_player(3, "carol");
+        let (mut game, turn) = ready_to_play(Game::new(), &[(&alice, "a clue")]);
+        assert!(matches!(game.ready(&alice), Err(Error::NoReadyCheck)));
+        game.configure("ready-check", "both").unwrap();
+        assert!(matches!(game.start_turn(), Err(Error::NotReady(waiting)) if waiting.len() == 2));
+        assert!(matches!(game.ready(&carol), Err(Error::PlayerNotAllowedToReady)));
+        assert!(game.ready(&turn.guesser).unwrap().is_none());
+        assert_eq!(game.waiting_on().unwrap(), vec![turn.performer.clone()]);
+        let (started, _) = game.ready(&turn.performer).unwrap().unwrap();
+        assert!(matches!(started.state, TurnState::Guessing(_)));
+        assert!(matches!(game.ready(&turn.performer), Err(Error::CurrentTurnNotYetFinished)));
+    }
+
+    #[test]
+    fn test_skipped_performer_sits_out() {
+        let players = (1..=3)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut game = Game::new();
+        players.iter().for_each(|p| game.add_player(p.clone()).unwrap());
+        game.add_clue(&Clue { entered_by: players[0].clone(), text: "a clue".to_string() }).unwrap();
+        start_round(&mut game);
+        let afk = game.prepare_turn().unwrap();
+        assert!(!game.skip_turn().unwrap());
+        let requeued = game.queue().unwrap().pop().unwrap();
+        assert_eq!((requeued.performer, requeued.guesser), (afk.performer.clone(), afk.guesser.clone()));
+
+        loop {
+            let turn = game.prepare_turn().unwrap();
+            if turn.performer == afk.performer {
+                break;
+            }
+            game.force_start_turn().unwrap();
+            game.end_turn(&turn.performer, &turn.guesser, 1).unwrap();
+        }
+        assert!(game.skip_turn().unwrap());
+        assert_eq!(game.inactive, vec![afk.performer.clone()]);
+        let queue = game.queue().unwrap();
+        assert!(queue.iter().all(|t| !t.involves(&afk.performer) && t.performer != t.guesser));
+        assert!(queue.iter().any(|t| t.guesser == afk.guesser));
+        assert!(!game.active_players().contains(&afk.performer));
+
+        assert!(matches!(game.back(&afk.guesser), Err(Error::NotSittingOut)));
+        game.back(&afk.performer).unwrap();
+        assert!(game.inactive.is_empty());
+        assert_eq!(game.active_players().len(), 3);
+    }
+
+    #[test]
+    fn test_no_afk_skips_never_sits_out() {
+        let mut game = Game::new();
+        game.add_player(test_player(1, "alice")).unwrap();
+        game.add_player(test_player(2, "bob")).unwrap();
+        game.add_clue(&Clue { entered_by: test_player(1, "alice"), text: "a clue".to_string() }).unwrap();
+        game.configure("afk-skips", "0").unwrap();
+        start_round(&mut game);
+        for _ in 0..3 {
+            game.prepare_turn().unwrap();
+            assert!(!game.skip_turn().unwrap());
+        }
+        assert!(game.inactive.is_empty());
+    }
+
+    #[test]
+    fn test_substitute_for_one_turn() {
+        let players = (1..=3)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut game = Game::new();
+        players.iter().for_each(|p| game.add_player(p.clone()).unwrap());
+        game.add_clue(&Clue { entered_by: players[0].clone(), text: "a clue".to_string() }).unwrap();
+        game.add_clue(&Clue { entered_by: players[1].clone(), text: "another".to_string() }).unwrap();
+        start_round(&mut game);
+        let turn = game.prepare_turn().unwrap();
+        let sub = players.iter().find(|p| !turn.involves(p)).unwrap().clone();
+        assert!(matches!(
+            game.substitute(&turn.performer, &[], turn.guesser.user.id, turn.performer.user.id),
+            Err(Error::AlreadyInTurn(_))));
+        let subbed = game.substitute(&turn.performer, &[], sub.user.id, turn.performer.user.id).unwrap();
+        assert_eq!(subbed.acting_performer(), &sub);
+
+        let (turn, round_number) = game.start_turn().unwrap();
+        let first = game.draw_first_clue().unwrap();
+        assert_eq!(first.performer, sub);
+        assert!(matches!(game.draw_clue(&turn.performer), Err(Error::PlayerNotAllowedToDrawAClue)));
+        game.draw_clue(&sub).unwrap();
+        game.end_turn(&turn.performer, &turn.guesser, round_number).unwrap();
+        let summary = game.turn_summary().unwrap();
+        assert_eq!(summary.substitutes(), &vec![Substitute { sub: sub.clone(), for_player: turn.performer.clone() }]);
+        assert!(summary.to_string().contains(&format!("{} stood in for {}", sub.name, turn.performer.name)));
+
+        let requeued = game.queue().unwrap().pop().unwrap();
+        assert_eq!(requeued.performer, turn.performer);
+        assert!(requeued.performer_sub.is_none());
+    }
+
+    #[test]
+    fn test_afk_sub_gives_the_turn_back() {
+        let players = (1..=3)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut game = Game::new();
+        players.iter().for_each(|p| game.add_player(p.clone()).unwrap());
+        game.add_clue(&Clue { entered_by: players[0].clone(), text: "a clue".to_string() }).unwrap();
+        game.configure("afk-skips", "1").unwrap();
+        start_round(&mut game);
+        let turn = game.prepare_turn().unwrap();
+        let sub = players.iter().find(|p| !turn.involves(p)).unwrap().clone();
+        game.substitute(&turn.performer, &[], sub.user.id, turn.performer.user.id).unwrap();
+
+        assert!(!game.skip_turn().unwrap());
+        assert!(game.inactive.is_empty());
+        assert!(game.skips.is_empty());
+        let requeued = game.queue().unwrap().pop().unwrap();
+        assert_eq!((requeued.performer, requeued.guesser), (turn.performer, turn.guesser));
+        assert!(requeued.performer_sub.is_none());
+    }
+
+    #[test]
+    fn test_whole_team_guesses() {
+        let players = (1..=6)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut game = Game::new();
+        players.iter().for_each(|p| game.add_player(p.clone()).unwrap());
+        game.configure("pairing", "team-guessing").unwrap();
+        game.configure("text-mode", "true").unwrap();
+        game.add_clue(&Clue { entered_by: players[0].clone(), text: "a clue".to_string() }).unwrap();
+        start_round(&mut game);
+        let turn = game.prepare_turn().unwrap();
+        let guessers = turn.guessers();
+        assert_eq!(guessers.len(), 2);
+        let other_team = players.iter()
+            .find(|p| *p != &turn.performer && !guessers.contains(p))
+            .unwrap();
+
+        game.bowl = Bowl::new();
+        game.bowl.add_clue(&Clue { entered_by: guessers[1].clone(), text: "theirs".to_string() });
+        game.bowl.add_clue(&Clue { entered_by: other_team.clone(), text: "ours".to_string() });
+        game.start_turn().unwrap();
+        assert_eq!(game.draw_first_clue().unwrap().clue.unwrap().text, "ours");
+        assert!(matches!(game.check_guess(other_team, "ours"), Err(Error::PlayerNotAllowedToGuess)));
+        assert!(game.check_guess(&guessers[1], "ours").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_substitute_for_a_team_guesser() {
+        let players = (1..=6)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut game = Game::new();
+        players.iter().for_each(|p| game.add_player(p.clone()).unwrap());
+        game.configure("pairing", "team-guessing").unwrap();
+        game.add_clue(&Clue { entered_by: players[0].clone(), text: "a clue".to_string() }).unwrap();
+        start_round(&mut game);
+        let turn = game.prepare_turn().unwrap();
+        let away = turn.team[0].clone();
+        let sub = players.iter().find(|p| !turn.involves(p)).unwrap().clone();
+        let subbed = game.substitute(&away, &[], sub.user.id, away.user.id).unwrap();
+        assert!(subbed.is_guessing(&sub));
+        assert!(!subbed.is_guessing(&away));
+        assert_eq!(subbed.guessers().len(), 2);
+        assert_eq!(subbed.substitutes(), vec![Substitute { sub, for_player: away.clone() }]);
+        assert_eq!(subbed.requeued().team, vec![away]);
+    }
+
+    #[tokio::test]
+    async fn test_carry_over_time_into_next_round() {
+        let alice = test_player(1, "alice");
+        let clock = Arc::new(ManualClock::new());
+        let (mut game, turn) = playing(Game::new().with_clock(clock.clone()), &[(&alice, "a clue")]);
+        game.configure("carry-over", "true").unwrap();
+        clock.advance(Duration::from_secs(15)).await;
+        assert!(game.draw_clue(&turn.performer).unwrap().clue.is_none());
+
+        let (summary, carried) = game.bowl_emptied().unwrap();
+        assert!(summary.to_string().contains("a clue"));
+        let (carried, round_number) = carried.unwrap();
+        assert_eq!(round_number, 2);
+        assert_eq!((&carried.performer, &carried.guesser), (&turn.performer, &turn.guesser));
+        assert_eq!(carried.time_left, Some(45));
+        assert!(matches!(carried.state, TurnState::Guessing(_)));
+        assert_eq!(game.queue().unwrap().len(), 2);
+
+        game.end_turn(&turn.performer, &turn.guesser, 2).unwrap();
+        assert!(game.queue().unwrap().iter().all(|t| t.time_left.is_none()));
+        assert_eq!(game.queue().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_last_clue_claimed_in_grace() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let (mut game, turn) = playing(Game::new(), &[(&alice, "one"), (&bob, "two")]);
+        game.start_grace(&turn.performer, &turn.guesser, 1).unwrap();
+
+        let last = game.draw_clue(&turn.performer).unwrap();
+        assert!(last.last_clue && last.clue.is_none());
+        assert!(matches!(game.draw_clue(&turn.performer), Err(Error::TimesUp)));
+        game.end_turn(&turn.performer, &turn.guesser, 1).unwrap();
+        assert!(!game.in_grace);
+        assert_eq!(game.bowl.num_unsolved(), 1);
+    }
+
+    #[test]
+    fn test_no_new_clues_in_grace() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let (mut game, turn) = playing(Game::new(), &[(&alice, "banana"), (&bob, "mango"), (&bob, "kiwi")]);
+        game.configure("guesser-confirms", "true").unwrap();
+        game.configure("relay", "guesser").unwrap();
+        game.configure("rules", "true").unwrap();
+        game.configure("fouls", "true").unwrap();
+        game.draw_clue(&turn.guesser).unwrap();
+        game.start_grace(&turn.performer, &turn.guesser, 1).unwrap();
+
+        let showing = game.bowl.showing().unwrap();
+        let outcome = game.relay(&turn.performer, &format!("it's {}", showing.text)).unwrap();
+        assert!(matches!(outcome, RelayOutcome::Blocked(_, None)));
+        assert_eq!(game.bowl.showing(), Some(showing));
+        assert!(matches!(game.dispute_clue(&turn.performer), Err(Error::TimesUp)));
+        assert!(game.draw_clue(&turn.guesser).unwrap().clue.is_none());
+        assert!(matches!(game.draw_clue(&turn.guesser), Err(Error::TimesUp)));
+    }
+
+    #[tokio::test]
+    async fn test_stale_clue_goes_back() {
+        let alice = test_player(1, "alice");
+        let bob = test_player(2, "bob");
+        let clock = Arc::new(ManualClock::new());
+        let (mut game, _) = playing(Game::new().with_clock(clock.clone()), &[(&alice, "one"), (&bob, "two")]);
+        game.configure("clue-limit", "20").unwrap();
+        game.configure("put-back", "cooldown 1").unwrap();
+        let first = game.bowl.showing().unwrap();
+
+        clock.advance(Duration::from_secs(19)).await;
+        assert!(game.return_stale_clue().unwrap().is_none());
+        clock.advance(Duration::from_secs(1)).await;
+        let next = game.return_stale_clue().unwrap().unwrap().clue.unwrap();
+        assert_ne!(next, first);
+        assert!(game.return_stale_clue().unwrap().is_none());
+        let summary = game.turn_summary().unwrap();
+        assert_eq!(summary.timed_out(), &vec![first]);
+        assert!(summary.to_string().contains("Clues that ran out of time: 1"));
+    }
+
+    #[test]
+    fn test_host_adds_time_and_ends_turn() {
+        let alice = test_player(1, "alice");
+        let clock = Arc::new(ManualClock::new());
+        let (mut game, _) = ready_to_play(Game::new().with_clock(clock), &[(&alice, "a clue")]);
+        assert!(matches!(game.add_time(30), Err(Error::CurrentTurnNotYetStarted)));
+        game.start_turn().unwrap();
+        assert_eq!(game.add_time(30).unwrap(), 90);
+        assert_eq!(game.add_time(-100).unwrap(), 0);
+        let timer = game.timer.clone().unwrap();
+        game.add_time(10).unwrap();
+        game.end_turn_early().unwrap();
+        assert_eq!(timer.time_left(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn test_undo_is_bounded() {
+        let alice = test_player(1, "alice");
+        let texts = (0..6).map(|i| i.to_string()).collect::<Vec<_>>();
+        let clues = texts.iter().map(|t| (&alice, t.as_str())).collect::<Vec<_>>();
+        let (mut game, turn) = playing(Game::new(), &clues);
+        assert!(matches!(game.undo(&turn.performer, &[]), Err(Error::NothingToUndo)));
+        let solved = (0..5)
+            .map(|_| {
+                let showing = game.bowl.showing().unwrap();
+                game.draw_clue(&turn.performer).unwrap();
+                showing
+            })
+            .collect::<Vec<_>>();
+
+        for clue in solved.iter().rev().take(UNDO_HISTORY) {
+            assert_eq!(game.undo(&turn.performer, &[]).unwrap().clue.as_ref(), Some(clue));
+            assert_eq!(game.bowl.showing().as_ref(), Some(clue));
+        }
+        assert!(matches!(game.undo(&turn.performer, &[]), Err(Error::NothingToUndo)));
+    }
+
+    #[test]
+    fn test_host_corrects_clues_between_turns() {
+        let alice = test_player(1, "alice");
+        let (mut game, turn) = playing(Game::new(), &[(&alice, "one"), (&alice, "two"), (&alice, "three")]);
+        let first = game.bowl.showing().unwrap();
+        let second = game.draw_clue(&turn.performer).unwrap().clue.unwrap();
+        assert!(matches!(game.correctable_clues(), Err(Error::CurrentTurnNotYetFinished)));
+        game.end_turn(&turn.performer, &turn.guesser, 1).unwrap();
+
+        let (solved, seen) = game.correctable_clues().unwrap();
+        assert_eq!(solved.iter().map(|(_, c)| c).collect::<Vec<_>>(), vec![&first]);
+        assert_eq!(seen.iter().map(|(_, c)| c).collect::<Vec<_>>(), vec![&second]);
+        assert!(matches!(game.mark_solved(0), Err(Error::NoSuchClue(0))));
+        assert!(matches!(game.mark_solved(solved[0].0), Err(Error::NoSuchClue(_))));
+
+        assert_eq!(game.mark_unsolved(solved[0].0).unwrap(), first);
+        assert!(!game.turn_summary().unwrap().to_string().contains("one"));
+        assert_eq!(game.mark_solved(seen[0].0).unwrap(), second);
+        assert!(game.turn_summary().unwrap().to_string().contains("two"));
+        assert_eq!(game.bowl.num_unsolved(), 2);
+    }
+}
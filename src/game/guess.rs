@@ -0,0 +1,80 @@
+/// Lowercases the text and drops accents, punctuation and spaces, so "Beyoncé!" and
+/// "beyonce" end up the same
+pub fn normalise(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .fold(String::new(), |mut acc, c| {
+            match c {
+                'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => acc.push('a'),
+                'æ' => acc.push_str("ae"),
+                'ç' | 'ć' | 'č' => acc.push('c'),
+                'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' => acc.push('e'),
+                'ì' | 'í' | 'î' | 'ï' | 'ī' => acc.push('i'),
+                'ñ' | 'ń' => acc.push('n'),
+                'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => acc.push('o'),
+                'œ' => acc.push_str("oe"),
+                'ß' => acc.push_str("ss"),
+                'ś' | 'š' => acc.push('s'),
+                'ù' | 'ú' | 'û' | 'ü' | 'ū' => acc.push('u'),
+                'ý' | 'ÿ' => acc.push('y'),
+                'ź' | 'ż' | 'ž' => acc.push('z'),
+                c if c.is_alphanumeric() => acc.push(c),
+                _ => (),
+            };
+            acc
+        })
+}
+
+/// Number of single character insertions, deletions or substitutions to get from `a` to `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if &ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Whether a typed guess is close enough to the clue. Case, accents, punctuation and
+/// spacing are ignored, and one typo is forgiven for every five letters in the answer
+pub fn is_correct(guess: &str, answer: &str) -> bool {
+    let guess = normalise(guess);
+    let answer = normalise(answer);
+    if guess.is_empty() {
+        return false;
+    }
+    edit_distance(&guess, &answer) <= answer.chars().count() / 5
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(normalise("Beyoncé!"), "beyonce");
+        assert_eq!(normalise("  Singin' in the Rain "), "singinintherain");
+        assert_eq!(normalise("ÆSOP'S FÁBLES"), "aesopsfables");
+    }
+
+    #[test]
+    fn test_is_correct() {
+        assert!(is_correct("the godfather", "The Godfather"));
+        assert!(is_correct("frida khalo", "Frida Kahlo"));
+        assert!(is_correct("leonardo davinchi", "Leonardo da Vinci"));
+        assert!(!is_correct("up", "Us"));
+        assert!(!is_correct("kettle", "Rocky"));
+        assert!(!is_correct("", "Up"));
+        assert!(!is_correct("the matrix", "The Godfather"));
+    }
+}
@@ -4,3 +4,10 @@ pub mod player;
 pub mod game_error;
 pub mod clue;
 pub mod bowl;
+pub mod word_list;
+pub mod settings;
+pub mod guess;
+pub mod rules;
+pub mod pairing;
+pub mod draw_policy;
+pub mod timer;
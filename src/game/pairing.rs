@@ -0,0 +1,373 @@
+use rand::{
+    RngCore,
+    seq::SliceRandom,
+};
+use std::fmt::{Display, Formatter, self};
+use std::str::FromStr;
+use crate::game::{
+    player::Player,
+    turn::Turn,
+};
+
+/// Decides who performs for whom. `Round::new` asks the game's strategy for the turns of
+/// each new round, in the order they'll be played. Any shuffling should use `rng`, so a
+/// game can be replayed from its seed
+pub trait PairingStrategy: Send + Sync {
+    fn turns(&mut self, round_number: i64, players: &[Player], rng: &mut dyn RngCore) -> Vec<Turn>;
+
+    /// Strategies that split players into teams say who's on which
+    fn teams(&self) -> Option<&Vec<Vec<Player>>> {
+        None
+    }
+}
+
+/// Which `PairingStrategy` the game uses, set with `!config pairing <name>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    ShuffledRing,
+    FixedRing,
+    Rotation,
+    Teams(usize),
+    TeamGuessing(usize),
+}
+
+impl Pairing {
+    pub fn strategy(&self) -> Box<dyn PairingStrategy> {
+        match self {
+            Pairing::ShuffledRing => Box::new(ShuffledRing),
+            Pairing::FixedRing => Box::new(FixedRing::new()),
+            Pairing::Rotation => Box::new(Rotation::new()),
+            Pairing::Teams(num_teams) => Box::new(TeamRing::new(*num_teams)),
+            Pairing::TeamGuessing(num_teams) => Box::new(TeamGuessing::new(*num_teams)),
+        }
+    }
+}
+
+impl FromStr for Pairing {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Pairing, ()> {
+        let mut words = s.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("shuffled"), None) => Ok(Pairing::ShuffledRing),
+            (Some("fixed"), None) => Ok(Pairing::FixedRing),
+            (Some("rotation"), None) => Ok(Pairing::Rotation),
+            (Some("teams"), None) => Ok(Pairing::Teams(2)),
+            (Some("teams"), Some(n)) => n.parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .map(Pairing::Teams)
+                .ok_or(()),
+            (Some("team-guessing"), None) => Ok(Pairing::TeamGuessing(2)),
+            (Some("team-guessing"), Some(n)) => n.parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .map(Pairing::TeamGuessing)
+                .ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Pairing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Pairing::ShuffledRing => write!(f, "shuffled"),
+            Pairing::FixedRing => write!(f, "fixed"),
+            Pairing::Rotation => write!(f, "rotation"),
+            Pairing::Teams(n) => write!(f, "teams {}", n),
+            Pairing::TeamGuessing(n) => write!(f, "team-guessing {}", n),
+        }
+    }
+}
+
+/// Everyone performs for the next person around a ring
+fn ring(players: &[Player], offset: usize) -> Vec<Turn> {
+    players.iter()
+        .zip(players.iter().cycle().skip(offset))
+        .map(|(p1, p2)| Turn::new(p1.clone(), p2.clone()))
+        .collect()
+}
+
+/// Keeps `order` as it was, minus anyone who's gone, plus anyone new on the end
+fn sync_order(order: &mut Vec<Player>, players: &[Player]) {
+    order.retain(|p| players.contains(p));
+    let new_players = players.iter()
+        .filter(|p| !order.contains(p))
+        .cloned()
+        .collect::<Vec<_>>();
+    order.extend(new_players);
+}
+
+/// A freshly shuffled ring every round
+pub struct ShuffledRing;
+
+impl PairingStrategy for ShuffledRing {
+    fn turns(&mut self, _round_number: i64, players: &[Player], rng: &mut dyn RngCore) -> Vec<Turn> {
+        let mut players = players.to_vec();
+        players.shuffle(rng);
+        ring(&players, 1)
+    }
+}
+
+/// The ring is shuffled once and kept for the whole game
+pub struct FixedRing {
+    order: Vec<Player>,
+}
+
+impl FixedRing {
+    pub fn new() -> FixedRing {
+        FixedRing { order: vec![] }
+    }
+}
+
+impl PairingStrategy for FixedRing {
+    fn turns(&mut self, _round_number: i64, players: &[Player], rng: &mut dyn RngCore) -> Vec<Turn> {
+        if self.order.is_empty() {
+            self.order = players.to_vec();
+            self.order.shuffle(rng);
+        }
+        sync_order(&mut self.order, players);
+        ring(&self.order, 1)
+    }
+}
+
+/// Each round everyone performs for someone further round the ring, so no performer and
+/// guesser pair comes up twice until every pair has had a go
+pub struct Rotation {
+    order: Vec<Player>,
+    used: Vec<(Player, Player)>,
+}
+
+impl Rotation {
+    pub fn new() -> Rotation {
+        Rotation { order: vec![], used: vec![] }
+    }
+}
+
+impl PairingStrategy for Rotation {
+    fn turns(&mut self, _round_number: i64, players: &[Player], rng: &mut dyn RngCore) -> Vec<Turn> {
+        if self.order.is_empty() {
+            self.order = players.to_vec();
+            self.order.shuffle(rng);
+        }
+        sync_order(&mut self.order, players);
+        let n = self.order.len();
+        if n < 2 {
+            return ring(&self.order, 1);
+        }
+        let unused = {
+            let used = &self.used;
+            let order = &self.order;
+            (1..n)
+                .map(|offset| ring(order, offset))
+                .find(|turns| turns.iter()
+                      .all(|t| !used.contains(&(t.performer.clone(), t.guesser.clone()))))
+        };
+        let turns = match unused {
+            Some(turns) => turns,
+            None => {
+                self.used.clear();
+                ring(&self.order, 1)
+            },
+        };
+        self.used.extend(turns.iter().map(|t| (t.performer.clone(), t.guesser.clone())));
+        turns
+    }
+}
+
+/// Players are split into teams at the start of the game and only ever perform for their
+/// teammates. Turns alternate between the teams
+pub struct TeamRing {
+    num_teams: usize,
+    teams: Vec<Vec<Player>>,
+}
+
+impl TeamRing {
+    pub fn new(num_teams: usize) -> TeamRing {
+        TeamRing { num_teams, teams: vec![] }
+    }
+}
+
+/// Splits everyone into `num_teams` teams the first time, after that anyone who's gone is
+/// taken off their team and anyone new joins the smallest one. Nobody can perform for
+/// themselves, so there's never a team of one unless there's only one player
+fn sync_teams(teams: &mut Vec<Vec<Player>>, num_teams: usize, players: &[Player], rng: &mut dyn RngCore) {
+    if teams.is_empty() {
+        let mut players = players.to_vec();
+        players.shuffle(rng);
+        let num_teams = num_teams.min(players.len() / 2).max(1);
+        *teams = (0..num_teams)
+            .map(|i| players.iter().skip(i).step_by(num_teams).cloned().collect())
+            .filter(|team: &Vec<Player>| !team.is_empty())
+            .collect();
+    }
+    teams.iter_mut()
+        .for_each(|team| team.retain(|p| players.contains(p)));
+    for p in players.iter() {
+        if !teams.iter().any(|team| team.contains(p)) {
+            if let Some(smallest) = teams.iter_mut().min_by_key(|team| team.len()) {
+                smallest.push(p.clone());
+            }
+        }
+    }
+    teams.retain(|team| !team.is_empty());
+    while let Some(i) = teams.iter().position(|team| team.len() < 2).filter(|_| teams.len() > 1) {
+        let lonely = teams.remove(i);
+        if let Some(smallest) = teams.iter_mut().min_by_key(|team| team.len()) {
+            smallest.extend(lonely);
+        }
+    }
+}
+
+/// Takes a turn from each team in turn, until they've all run out
+fn alternate(team_turns: Vec<Vec<Turn>>) -> Vec<Turn> {
+    let longest = team_turns.iter().map(Vec::len).max().unwrap_or(0);
+    (0..longest)
+        .flat_map(|i| team_turns.iter().filter_map(move |turns| turns.get(i).cloned()))
+        .collect()
+}
+
+impl PairingStrategy for TeamRing {
+    fn turns(&mut self, _round_number: i64, players: &[Player], rng: &mut dyn RngCore) -> Vec<Turn> {
+        sync_teams(&mut self.teams, self.num_teams, players, rng);
+        let team_turns = self.teams.iter()
+            .map(|team| {
+                let mut team = team.clone();
+                team.shuffle(rng);
+                ring(&team, 1)
+            })
+            .collect::<Vec<_>>();
+        alternate(team_turns)
+    }
+
+    fn teams(&self) -> Option<&Vec<Vec<Player>>> {
+        Some(&self.teams)
+    }
+}
+
+/// Classic fishbowl: players are split into teams at the start of the game, and everyone
+/// on the performer's team guesses. Everyone performs once a round, alternating between teams
+pub struct TeamGuessing {
+    num_teams: usize,
+    teams: Vec<Vec<Player>>,
+}
+
+impl TeamGuessing {
+    pub fn new(num_teams: usize) -> TeamGuessing {
+        TeamGuessing { num_teams, teams: vec![] }
+    }
+}
+
+impl PairingStrategy for TeamGuessing {
+    fn turns(&mut self, _round_number: i64, players: &[Player], rng: &mut dyn RngCore) -> Vec<Turn> {
+        sync_teams(&mut self.teams, self.num_teams, players, rng);
+        let team_turns = self.teams.iter()
+            .map(|team| {
+                let mut performers = team.clone();
+                performers.shuffle(rng);
+                performers.into_iter()
+                    .map(|performer| {
+                        let guessers = team.iter()
+                            .filter(|p| *p != &performer)
+                            .cloned()
+                            .collect();
+                        Turn::for_team(performer, guessers)
+                    })
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        alternate(team_turns)
+    }
+
+    fn teams(&self) -> Option<&Vec<Vec<Player>>> {
+        Some(&self.teams)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::game::player::test_player;
+
+    fn pairs(turns: &[Turn]) -> Vec<(String, String)> {
+        turns.iter()
+            .map(|t| (t.performer.name.clone(), t.guesser.name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rotation_uses_every_pair_before_repeating() {
+        let players = (1..=4)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut rotation = Rotation::new();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut seen = (1..=3)
+            .flat_map(|round| pairs(&rotation.turns(round, &players, &mut rng)))
+            .collect::<Vec<_>>();
+        assert_eq!(seen.len(), 12);
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 12);
+        assert!(seen.iter().all(|(p, g)| p != g));
+    }
+
+    #[test]
+    fn test_team_ring_keeps_guessers_on_the_same_team() {
+        let players = (1..=6)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut teams = TeamRing::new(2);
+        let turns = teams.turns(1, &players, &mut StdRng::seed_from_u64(0));
+        assert_eq!(turns.len(), 6);
+        let team_of = |p: &Player| teams.teams()
+            .unwrap()
+            .iter()
+            .position(|team| team.contains(p));
+        assert!(turns.iter().all(|t| team_of(&t.performer) == team_of(&t.guesser)));
+        assert!(turns.windows(2).all(|w| team_of(&w[0].performer) != team_of(&w[1].performer)));
+    }
+
+    #[test]
+    fn test_nobody_is_left_on_a_team_of_one() {
+        let players = (1..=4)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut teams = TeamRing::new(3);
+        let turns = teams.turns(1, &players, &mut rng);
+        assert_eq!(turns.len(), 4);
+        assert!(turns.iter().all(|t| t.performer != t.guesser));
+        assert!(teams.teams().unwrap().iter().all(|team| team.len() >= 2));
+
+        let turns = teams.turns(2, &players[..3], &mut rng);
+        assert_eq!(teams.teams().unwrap().len(), 1);
+        assert!(turns.iter().all(|t| t.performer != t.guesser));
+    }
+
+    #[test]
+    fn test_team_guessing_has_the_whole_team_guess() {
+        let players = (1..=6)
+            .map(|i| test_player(i, &i.to_string()))
+            .collect::<Vec<_>>();
+        let mut teams = TeamGuessing::new(2);
+        let turns = teams.turns(1, &players, &mut StdRng::seed_from_u64(0));
+        assert_eq!(turns.len(), 6);
+        let team_of = |p: &Player| teams.teams()
+            .unwrap()
+            .iter()
+            .position(|team| team.contains(p));
+        for t in turns.iter() {
+            let guessers = t.guessers();
+            assert_eq!(guessers.len(), 2);
+            assert!(!guessers.contains(&t.performer));
+            assert!(guessers.iter().all(|g| team_of(g) == team_of(&t.performer)));
+        }
+        assert!(turns.windows(2).all(|w| team_of(&w[0].performer) != team_of(&w[1].performer)));
+
+        let turns = TeamGuessing::new(3).turns(1, &players[..4], &mut StdRng::seed_from_u64(0));
+        assert!(turns.iter().all(|t| !t.guessers().contains(&t.performer)));
+    }
+}
@@ -24,3 +24,10 @@ impl Display for Player {
         write!(f, "{} ({})", self.name, self.user)
     }
 }
+
+#[cfg(test)]
+pub fn test_player(id: u64, name: &str) -> Player {
+    use serenity::model::id::UserId;
+    let user = User { id: UserId(id), name: name.to_string(), bot: false, ..Default::default() };
+    (&user).into()
+}
@@ -0,0 +1,167 @@
+use std::fmt::{Display, Formatter, self};
+use crate::game::{
+    clue::Clue,
+    guess::normalise,
+};
+
+/// Little words that don't give anything away, so saying them isn't a foul
+const IGNORED_WORDS: &[&str] = &["a", "an", "and", "in", "of", "on", "the", "to"];
+
+/// What the performer's allowed to say in each round, as the README describes them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundRule {
+    AnyWordsButTheClue,
+    OneWord,
+    EmojiOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Foul {
+    SaidTheClue(String),
+    MoreThanOneWord,
+    NotJustEmoji,
+}
+
+impl Display for Foul {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Foul::SaidTheClue(word) =>
+                write!(f, "\"{}\" is on the clue", word),
+            Foul::MoreThanOneWord =>
+                write!(f, "it's one word only this round"),
+            Foul::NotJustEmoji =>
+                write!(f, "it's charades this round, emoji only"),
+        }
+    }
+}
+
+impl RoundRule {
+    /// Rounds after the third are up to your imagination, so anything goes
+    pub fn for_round(round_number: i64) -> Option<RoundRule> {
+        match round_number {
+            1 => Some(RoundRule::AnyWordsButTheClue),
+            2 => Some(RoundRule::OneWord),
+            3 => Some(RoundRule::EmojiOnly),
+            _ => None,
+        }
+    }
+
+    pub fn check(&self, text: &str, clue: &Clue) -> Result<(), Foul> {
+        match self {
+            RoundRule::AnyWordsButTheClue => check_not_on_clue(text, clue),
+            RoundRule::OneWord => {
+                if words(text).len() > 1 {
+                    Err(Foul::MoreThanOneWord)
+                } else {
+                    check_not_on_clue(text, clue)
+                }
+            },
+            RoundRule::EmojiOnly => {
+                if strip_custom_emoji(text).chars().all(|c| c.is_whitespace() || is_emoji(c)) {
+                    Ok(())
+                } else {
+                    Err(Foul::NotJustEmoji)
+                }
+            },
+        }
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(normalise)
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// A very rough stemmer, the word itself along with it minus any common ending, so
+/// "raining" gives away "rain" and "lighthouses" gives away "lighthouse"
+fn stems(word: &str) -> Vec<&str> {
+    let mut stems = vec![word];
+    stems.extend(["ing", "ed", "er", "ly", "es", "s"].iter()
+        .filter(|suffix| word.ends_with(*suffix) && word.len() - suffix.len() >= 3)
+        .map(|suffix| &word[..word.len() - suffix.len()]));
+    stems
+}
+
+fn check_not_on_clue(text: &str, clue: &Clue) -> Result<(), Foul> {
+    let clue_words = words(&clue.text)
+        .into_iter()
+        .filter(|w| !IGNORED_WORDS.contains(&w.as_str()))
+        .collect::<Vec<_>>();
+    let clue_stems = clue_words.iter()
+        .flat_map(|w| stems(w))
+        .collect::<Vec<_>>();
+    match words(text).into_iter().find(|w| stems(w).iter().any(|s| clue_stems.contains(s))) {
+        Some(word) => Err(Foul::SaidTheClue(word)),
+        None => Ok(()),
+    }
+}
+
+/// Discord's custom emoji look like `<:name:id>` or `<a:name:id>`
+fn strip_custom_emoji(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let (before, after) = rest.split_at(start);
+        stripped.push_str(before);
+        let is_custom = after.starts_with("<:") || after.starts_with("<a:");
+        match after.find('>') {
+            Some(end) if is_custom => rest = &after[end + 1..],
+            _ => {
+                stripped.push('<');
+                rest = &after[1..];
+            },
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF
+        | 0x2300..=0x23FF
+        | 0x2600..=0x27BF
+        | 0x2B00..=0x2BFF
+        | 0x3030 | 0x303D | 0x00A9 | 0x00AE | 0x2122 | 0x20E3
+        | 0x200D | 0xFE0F
+        | 0xE0020..=0xE007F)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::test_player;
+
+    fn clue(text: &str) -> Clue {
+        Clue { entered_by: test_player(1, "alice"), text: text.to_string() }
+    }
+
+    #[test]
+    fn test_any_words_but_the_clue() {
+        let rule = RoundRule::AnyWordsButTheClue;
+        let clue = clue("Singin' in the Rain");
+        assert_eq!(rule.check("Gene Kelly dancing with an umbrella", &clue), Ok(()));
+        assert_eq!(rule.check("the weather is RAINING", &clue), Err(Foul::SaidTheClue("raining".to_string())));
+        assert_eq!(rule.check("it's raining men", &clue), Err(Foul::SaidTheClue("raining".to_string())));
+    }
+
+    #[test]
+    fn test_one_word() {
+        let rule = RoundRule::OneWord;
+        let clue = clue("Lighthouse");
+        assert_eq!(rule.check("coast!", &clue), Ok(()));
+        assert_eq!(rule.check("boats crash", &clue), Err(Foul::MoreThanOneWord));
+        assert_eq!(rule.check("lighthouses", &clue), Err(Foul::SaidTheClue("lighthouses".to_string())));
+    }
+
+    #[test]
+    fn test_emoji_only() {
+        let rule = RoundRule::EmojiOnly;
+        let clue = clue("Titanic");
+        assert_eq!(rule.check("🚢 🧊 💔", &clue), Ok(()));
+        assert_eq!(rule.check("<:iceberg:1234> ❤️", &clue), Ok(()));
+        assert_eq!(rule.check("🚢 sinks", &clue), Err(Foul::NotJustEmoji));
+    }
+}
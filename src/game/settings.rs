@@ -0,0 +1,281 @@
+use rand::{
+    thread_rng,
+    Rng,
+};
+use std::fmt::{Display, Formatter, self};
+use std::str::FromStr;
+use crate::{
+    error::Error,
+    game::{
+        pairing::Pairing,
+        draw_policy::Draw,
+        bowl::PutBack,
+        player::Player,
+        turn::Turn,
+        timer::WARNING_SECS,
+    },
+};
+
+/// Where the performer's DMs get passed on to in relay mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relay {
+    Off,
+    Guesser,
+    Channel,
+}
+
+impl FromStr for Relay {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Relay, ()> {
+        match s.to_lowercase().as_str() {
+            "off" | "false" => Ok(Relay::Off),
+            "guesser" => Ok(Relay::Guesser),
+            "channel" => Ok(Relay::Channel),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Relay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Relay::Off => write!(f, "off"),
+            Relay::Guesser => write!(f, "guesser"),
+            Relay::Channel => write!(f, "channel"),
+        }
+    }
+}
+
+/// Who has to say they're ready before a turn can start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadyCheck {
+    Off,
+    Performer,
+    Both,
+}
+
+impl ReadyCheck {
+    pub fn players(&self, turn: &Turn) -> Vec<Player> {
+        match self {
+            ReadyCheck::Off => vec![],
+            ReadyCheck::Performer => vec![turn.acting_performer().clone()],
+            ReadyCheck::Both => {
+                let mut players = vec![turn.acting_performer().clone()];
+                players.extend(turn.guessers()
+                               .into_iter()
+                               .filter(|g| g != turn.acting_performer()));
+                players
+            },
+        }
+    }
+}
+
+impl FromStr for ReadyCheck {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ReadyCheck, ()> {
+        match s.to_lowercase().as_str() {
+            "off" | "false" => Ok(ReadyCheck::Off),
+            "performer" | "true" => Ok(ReadyCheck::Performer),
+            "both" => Ok(ReadyCheck::Both),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for ReadyCheck {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadyCheck::Off => write!(f, "off"),
+            ReadyCheck::Performer => write!(f, "performer"),
+            ReadyCheck::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Per-game options the host can change with `!config <key> <value>`
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub num_rounds: i64,
+    pub turn_length: u64,
+    pub guesser_confirms: bool,
+    pub text_mode: bool,
+    pub relay: Relay,
+    pub enforce_rules: bool,
+    pub fouls: bool,
+    pub pairing: Pairing,
+    pub draw: Draw,
+    pub put_back: PutBack,
+    pub ready_check: ReadyCheck,
+    /// Seconds a turn can sit unstarted before the performer's reminded, and the same
+    /// again before it's skipped. 0 never skips
+    pub afk_timeout: u64,
+    /// Skips in a row before a player sits out. 0 never sits anyone out
+    pub afk_skips: usize,
+    /// The bot runs the turns and rounds itself
+    pub auto_pilot: bool,
+    /// Seconds auto-pilot gives the next pair to get ready
+    pub countdown: u64,
+    /// When the bowl runs out partway through a turn, the performer keeps going in the next
+    /// round with the time they had left
+    pub carry_over: bool,
+    /// Seconds after time's up that the performer can still claim the last clue
+    pub grace: u64,
+    /// Seconds a clue can be showing before it goes back in the bowl and the performer gets
+    /// another. 0 lets them keep at it for as long as they like
+    pub clue_limit: u64,
+    /// Everything random in the game comes from this, so the same seed and the same
+    /// commands play out the same way
+    pub seed: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            num_rounds: 3,
+            turn_length: 60,
+            guesser_confirms: false,
+            text_mode: false,
+            relay: Relay::Off,
+            enforce_rules: false,
+            fouls: false,
+            pairing: Pairing::ShuffledRing,
+            draw: Draw::AvoidGuesser,
+            put_back: PutBack::Shuffle,
+            ready_check: ReadyCheck::Off,
+            afk_timeout: 120,
+            afk_skips: 2,
+            auto_pilot: false,
+            countdown: 10,
+            carry_over: false,
+            grace: 3,
+            clue_limit: 0,
+            seed: thread_rng().gen(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        match key {
+            "rounds" => {
+                self.num_rounds = parse_at_least(key, value, 1)?;
+                Ok(())
+            },
+            "turn-length" => {
+                // Any shorter and the warning goes off before the turn's even started
+                self.turn_length = parse_at_least(key, value, WARNING_SECS + 1)?;
+                Ok(())
+            },
+            "guesser-confirms" => {
+                self.guesser_confirms = parse_value(key, value)?;
+                Ok(())
+            },
+            "text-mode" => {
+                self.text_mode = parse_value(key, value)?;
+                Ok(())
+            },
+            "relay" => {
+                self.relay = parse_value(key, value)?;
+                Ok(())
+            },
+            "rules" => {
+                self.enforce_rules = parse_value(key, value)?;
+                Ok(())
+            },
+            "fouls" => {
+                self.fouls = parse_value(key, value)?;
+                Ok(())
+            },
+            "pairing" => {
+                self.pairing = parse_value(key, value)?;
+                Ok(())
+            },
+            "draw" => {
+                self.draw = parse_value(key, value)?;
+                Ok(())
+            },
+            "put-back" => {
+                self.put_back = parse_value(key, value)?;
+                Ok(())
+            },
+            "ready-check" => {
+                self.ready_check = parse_value(key, value)?;
+                Ok(())
+            },
+            "afk-timeout" => {
+                self.afk_timeout = parse_value(key, value)?;
+                Ok(())
+            },
+            "afk-skips" => {
+                self.afk_skips = parse_value(key, value)?;
+                Ok(())
+            },
+            "auto-pilot" => {
+                self.auto_pilot = parse_value(key, value)?;
+                Ok(())
+            },
+            "countdown" => {
+                self.countdown = parse_value(key, value)?;
+                Ok(())
+            },
+            "carry-over" => {
+                self.carry_over = parse_value(key, value)?;
+                Ok(())
+            },
+            "grace" => {
+                self.grace = parse_value(key, value)?;
+                Ok(())
+            },
+            "clue-limit" => {
+                self.clue_limit = parse_value(key, value)?;
+                Ok(())
+            },
+            "seed" => {
+                self.seed = parse_value(key, value)?;
+                Ok(())
+            },
+            _ => Err(Error::UnknownSetting(key.to_string())),
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, Error> {
+    value.parse()
+        .map_err(|_| Error::BadSetting { key: key.to_string(), value: value.to_string() })
+}
+
+fn parse_at_least<T: FromStr + PartialOrd>(key: &str, value: &str, min: T) -> Result<T, Error> {
+    parse_value(key, value)
+        .and_then(|v| if v >= min {
+            Ok(v)
+        } else {
+            Err(Error::BadSetting { key: key.to_string(), value: value.to_string() })
+        })
+}
+
+impl Display for Settings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "rounds: {}\nturn-length: {}s\nguesser-confirms: {}\ntext-mode: {}\nrelay: {}\nrules: {}\nfouls: {}\npairing: {}\ndraw: {}\nput-back: {}\nready-check: {}\nafk-timeout: {}s\nafk-skips: {}\nauto-pilot: {}\ncountdown: {}s\ncarry-over: {}\ngrace: {}s\nclue-limit: {}s\nseed: {}",
+               self.num_rounds,
+               self.turn_length,
+               self.guesser_confirms,
+               self.text_mode,
+               self.relay,
+               self.enforce_rules,
+               self.fouls,
+               self.pairing,
+               self.draw,
+               self.put_back,
+               self.ready_check,
+               self.afk_timeout,
+               self.afk_skips,
+               self.auto_pilot,
+               self.countdown,
+               self.carry_over,
+               self.grace,
+               self.clue_limit,
+               self.seed)
+    }
+}
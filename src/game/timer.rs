@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex};
+use tokio::{
+    sync::Notify,
+    time::Duration,
+};
+use crate::clock::Clock;
+
+/// Everyone's warned this many seconds before a turn ends
+pub const WARNING_SECS: u64 = 10;
+
+/// Times a turn on the game's clock. Wait for `warning` first, then `times_up`, then
+/// `grace_over`. The host can move the end of the turn while it's going, and anything
+/// waiting keeps up
+pub struct TurnTimer {
+    clock: Arc<dyn Clock>,
+    ends_at: Mutex<Duration>,
+    changed: Notify,
+    grace: u64,
+}
+
+impl TurnTimer {
+    /// Starts the turn now
+    pub fn new(clock: Arc<dyn Clock>, turn_length: u64) -> TurnTimer {
+        let ends_at = Mutex::new(clock.now() + Duration::from_secs(turn_length));
+        TurnTimer { clock, ends_at, changed: Notify::new(), grace: 0 }
+    }
+
+    /// Seconds after time's up that the last clue can still be claimed, see `Settings::grace`
+    pub fn with_grace(self, grace: u64) -> TurnTimer {
+        TurnTimer { grace, ..self }
+    }
+
+    pub fn time_left(&self) -> Duration {
+        self.ends_at.lock().unwrap().saturating_sub(self.clock.now())
+    }
+
+    /// Moves the end of the turn `secs` later, or earlier if it's negative (but not before now)
+    pub fn add_time(&self, secs: i64) {
+        {
+            let mut ends_at = self.ends_at.lock().unwrap();
+            let by = Duration::from_secs(secs.unsigned_abs());
+            *ends_at = if secs >= 0 {
+                *ends_at + by
+            } else {
+                ends_at.saturating_sub(by).max(self.clock.now())
+            };
+        }
+        self.changed.notify_waiters();
+    }
+
+    /// Time's up, right now
+    pub fn end_now(&self) {
+        *self.ends_at.lock().unwrap() = self.clock.now();
+        self.changed.notify_waiters();
+    }
+
+    /// Waits until it's time for the "TEN SECONDS LEFT!!" warning. Returns whether there's
+    /// any time left to warn about, there won't be if the turn was ended early
+    pub async fn warning(&self) -> bool {
+        let warning = Duration::from_secs(WARNING_SECS);
+        loop {
+            let changed = self.changed.notified();
+            let wait = self.time_left().saturating_sub(warning);
+            if wait == Duration::from_secs(0) {
+                return self.time_left() > Duration::from_secs(0);
+            }
+            tokio::select! {
+                _ = self.clock.sleep(wait) => (),
+                _ = changed => (),
+            }
+        }
+    }
+
+    /// Waits out the rest of the turn after the warning. Returns false instead if enough
+    /// time's been added that there's another warning to come
+    pub async fn times_up(&self) -> bool {
+        loop {
+            let changed = self.changed.notified();
+            let left = self.time_left();
+            if left == Duration::from_secs(0) {
+                return true;
+            }
+            if left > Duration::from_secs(WARNING_SECS) {
+                return false;
+            }
+            tokio::select! {
+                _ = self.clock.sleep(left) => (),
+                _ = changed => (),
+            }
+        }
+    }
+
+    /// Waits out the grace period after time's up
+    pub async fn grace_over(&self) {
+        self.clock
+            .sleep(Duration::from_secs(self.grace))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[tokio::test]
+    async fn test_turn_timer() {
+        let clock = Arc::new(ManualClock::new());
+        let events = Arc::new(Mutex::new(vec![]));
+        let timer = TurnTimer::new(clock.clone(), 60).with_grace(3);
+        let turn = {
+            let events = events.clone();
+            tokio::spawn(async move {
+                timer.warning().await;
+                events.lock().unwrap().push("TEN SECONDS LEFT!!");
+                timer.times_up().await;
+                events.lock().unwrap().push("Time's up!");
+                timer.grace_over().await;
+                events.lock().unwrap().push("Recap");
+            })
+        };
+        let seconds = |s| Duration::from_secs(s);
+        clock.advance(seconds(49)).await;
+        assert!(events.lock().unwrap().is_empty());
+        clock.advance(seconds(1)).await;
+        assert_eq!(*events.lock().unwrap(), vec!["TEN SECONDS LEFT!!"]);
+        clock.advance(seconds(9)).await;
+        assert_eq!(events.lock().unwrap().len(), 1);
+        clock.advance(seconds(1)).await;
+        assert_eq!(*events.lock().unwrap(), vec!["TEN SECONDS LEFT!!", "Time's up!"]);
+        clock.advance(seconds(3)).await;
+        turn.await.unwrap();
+        assert_eq!(*events.lock().unwrap(), vec!["TEN SECONDS LEFT!!", "Time's up!", "Recap"]);
+    }
+
+    #[tokio::test]
+    async fn test_short_turn_has_no_wait_before_warning() {
+        let clock = Arc::new(ManualClock::new());
+        let timer = TurnTimer::new(clock.clone(), 5);
+        timer.warning().await;
+        let turn = tokio::spawn(async move { timer.times_up().await });
+        clock.advance(Duration::from_secs(5)).await;
+        turn.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_adding_and_ending_time() {
+        let clock = Arc::new(ManualClock::new());
+        let timer = Arc::new(TurnTimer::new(clock.clone(), 30));
+        let seconds = |s| Duration::from_secs(s);
+        clock.advance(seconds(20)).await;
+        assert!(timer.warning().await);
+        let waiting = {
+            let timer = timer.clone();
+            tokio::spawn(async move { timer.times_up().await })
+        };
+        clock.advance(seconds(5)).await;
+        timer.add_time(20);
+        assert!(!waiting.await.unwrap());
+        assert_eq!(timer.time_left(), seconds(25));
+
+        let waiting = {
+            let timer = timer.clone();
+            tokio::spawn(async move { timer.warning().await })
+        };
+        clock.advance(seconds(1)).await;
+        timer.end_now();
+        assert!(!waiting.await.unwrap());
+        assert!(timer.times_up().await);
+    }
+}
@@ -7,14 +7,24 @@ use crate::game::{
 
 #[derive(Clone, Debug)]
 pub enum TurnState {
-    Ready,
+    /// Along with everyone who's said they're ready so far
+    Ready(Vec<Player>),
     Guessing(TurnSummary),
     Ended(TurnSummary),
 }
 
+/// Someone standing in for a player for a single turn, see `Turn::with_substitute`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Substitute {
+    pub sub: Player,
+    pub for_player: Player,
+}
+
 #[derive(Clone, Debug)]
 pub struct TurnSummary {
-    clues_solved: Vec<Clue>
+    clues_solved: Vec<Clue>,
+    substitutes: Vec<Substitute>,
+    timed_out: Vec<Clue>,
 }
 
 impl Display for TurnSummary {
@@ -26,7 +36,15 @@ impl Display for TurnSummary {
                 .iter()
                 .map(ToString::to_string)
                 .collect::<Vec<_>>()
-                .join("\n"))
+                .join("\n"))?;
+        // Just how many, they're back in the bowl so it'd be a spoiler
+        if !self.timed_out.is_empty() {
+            write!(f, "\nClues that ran out of time: {}", self.timed_out.len())?;
+        }
+        for s in self.substitutes.iter() {
+            write!(f, "\n{} stood in for {}", s.sub.name, s.for_player.name)?;
+        }
+        Ok(())
     }
 }
 
@@ -34,6 +52,8 @@ impl TurnSummary {
     pub fn new() -> TurnSummary {
         TurnSummary {
             clues_solved: vec![],
+            substitutes: vec![],
+            timed_out: vec![],
         }
     }
 
@@ -42,16 +62,69 @@ impl TurnSummary {
             clues_solved: self.clues_solved
                 .into_iter()
                 .chain(vec![clue])
-                .collect()
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn without_last_clue(self) -> (Option<Clue>, TurnSummary) {
+        let mut clues_solved = self.clues_solved;
+        let clue = clues_solved.pop();
+        (clue, TurnSummary { clues_solved, ..self })
+    }
+
+    /// Takes the clue off the turn's solved clues, if it's there
+    pub fn without_clue(self, clue: &Clue) -> TurnSummary {
+        let mut clues_solved = self.clues_solved;
+        if let Some(i) = clues_solved.iter().rposition(|c| c == clue) {
+            clues_solved.remove(i);
+        }
+        TurnSummary { clues_solved, ..self }
+    }
+
+    /// A clue went back in the bowl because it was showing for too long, see `Settings::clue_limit`
+    pub fn with_timed_out(self, clue: Clue) -> TurnSummary {
+        TurnSummary {
+            timed_out: self.timed_out
+                .into_iter()
+                .chain(vec![clue])
+                .collect(),
+            ..self
         }
     }
+
+    pub fn timed_out(&self) -> &Vec<Clue> {
+        &self.timed_out
+    }
+
+    pub fn with_substitutes(self, substitutes: Vec<Substitute>) -> TurnSummary {
+        TurnSummary { substitutes, ..self }
+    }
+
+    /// Who stood in for whom this turn, so the clues can be credited to the right people
+    pub fn substitutes(&self) -> &Vec<Substitute> {
+        &self.substitutes
+    }
 }
 
+/// `performer` and `guesser` are who the pairing says, and stay that way even if someone
+/// stands in for one of them this turn. `acting_performer` and `acting_guesser` are who's
+/// actually playing
 #[derive(Clone, Debug)]
 pub struct Turn {
     pub performer: Player,
     pub guesser: Player,
-    pub state: TurnState
+    pub state: TurnState,
+    pub performer_sub: Option<Player>,
+    pub guesser_sub: Option<Player>,
+    /// When the whole team guesses, the rest of the performer's team guessing along with
+    /// `guesser`. Empty otherwise
+    pub team: Vec<Player>,
+    /// Anyone standing in for someone in `team` this turn
+    pub team_subs: Vec<Substitute>,
+    /// Seconds left over from last round, see `Settings::carry_over`. The turn lasts this
+    /// long instead of the usual turn length, and it's a one-off so isn't requeued
+    pub time_left: Option<u64>,
 }
 
 impl Turn {
@@ -59,36 +132,166 @@ impl Turn {
         Turn {
             performer: p1,
             guesser: p2,
-            state: TurnState::Ready
+            state: TurnState::Ready(vec![]),
+            performer_sub: None,
+            guesser_sub: None,
+            team: vec![],
+            team_subs: vec![],
+            time_left: None,
+        }
+    }
+
+    /// A turn where everyone in `guessers` guesses. Teams always have someone else on them
+    /// (see `pairing::sync_teams`), so there's only nobody to guess in a one player game,
+    /// and then the performer guesses for themselves like a one player ring
+    pub fn for_team(performer: Player, guessers: Vec<Player>) -> Turn {
+        let mut guessers = guessers.into_iter();
+        let guesser = guessers.next().unwrap_or_else(|| performer.clone());
+        Turn {
+            team: guessers.collect(),
+            ..Turn::new(performer, guesser)
+        }
+    }
+
+    /// The same performer and guessers, ready to go again later
+    pub fn requeued(&self) -> Turn {
+        let guessers = vec![self.guesser.clone()]
+            .into_iter()
+            .chain(self.team.iter().cloned())
+            .collect();
+        Turn::for_team(self.performer.clone(), guessers)
+    }
+
+    /// Everyone guessing this turn
+    pub fn guessers(&self) -> Vec<Player> {
+        vec![self.acting_guesser().clone()]
+            .into_iter()
+            .chain(self.acting_team())
+            .collect()
+    }
+
+    /// Who's actually guessing along with `guesser`, after any substitutes
+    pub fn acting_team(&self) -> Vec<Player> {
+        self.team
+            .iter()
+            .map(|p| self.team_subs
+                 .iter()
+                 .find(|s| &s.for_player == p)
+                 .map(|s| s.sub.clone())
+                 .unwrap_or_else(|| p.clone()))
+            .collect()
+    }
+
+    pub fn is_guessing(&self, p: &Player) -> bool {
+        self.acting_guesser() == p || self.acting_team().contains(p)
+    }
+
+    /// Who's guessing, for messages
+    pub fn guessing(&self) -> String {
+        self.guessers()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The turn without `p` guessing, and `replacement` guessing instead if there'd be
+    /// nobody left. `None` if there's still nobody to guess
+    pub fn without_guesser(self, p: &Player, replacement: Option<&Player>) -> Option<Turn> {
+        if !self.is_guessing(p) && &self.guesser != p {
+            return Some(self);
+        }
+        let mut guessers = vec![self.guesser.clone()]
+            .into_iter()
+            .chain(self.team.iter().cloned())
+            .filter(|g| g != p)
+            .collect::<Vec<_>>();
+        if guessers.is_empty() {
+            guessers.extend(replacement
+                            .filter(|r| *r != &self.performer && *r != p)
+                            .cloned());
+        }
+        let mut guessers = guessers.into_iter();
+        let guesser = guessers.next()?;
+        let guesser_sub = self.guesser_sub.clone().filter(|_| guesser == self.guesser);
+        let team_subs = self.team_subs
+            .iter()
+            .filter(|s| &s.for_player != p)
+            .cloned()
+            .collect();
+        Some(Turn { guesser, guesser_sub, team: guessers.collect(), team_subs, ..self })
+    }
+
+    pub fn acting_performer(&self) -> &Player {
+        self.performer_sub.as_ref().unwrap_or(&self.performer)
+    }
+
+    pub fn acting_guesser(&self) -> &Player {
+        self.guesser_sub.as_ref().unwrap_or(&self.guesser)
+    }
+
+    /// Whether `p` is in this turn, either as themselves or standing in for someone
+    pub fn involves(&self, p: &Player) -> bool {
+        [&self.performer, &self.guesser, self.acting_performer(), self.acting_guesser()].contains(&p)
+            || self.team.contains(p)
+            || self.team_subs.iter().any(|s| &s.sub == p)
+    }
+
+    /// Has `sub` perform or guess in place of `for_player`, just for this turn
+    pub fn with_substitute(self, sub: Player, for_player: &Player) -> Option<Turn> {
+        if for_player == self.acting_performer() || for_player == &self.performer {
+            Some(Turn { performer_sub: Some(sub), ..self })
+        } else if for_player == self.acting_guesser() || for_player == &self.guesser {
+            Some(Turn { guesser_sub: Some(sub), ..self })
+        } else {
+            // Someone on the team, or someone already standing in for them
+            let for_player = self.team_subs
+                .iter()
+                .find(|s| &s.sub == for_player)
+                .map(|s| s.for_player.clone())
+                .or_else(|| Some(for_player.clone()).filter(|p| self.team.contains(p)))?;
+            let mut team_subs = self.team_subs.clone();
+            team_subs.retain(|s| s.for_player != for_player);
+            team_subs.push(Substitute { sub, for_player });
+            Some(Turn { team_subs, ..self })
         }
     }
 
+    pub fn substitutes(&self) -> Vec<Substitute> {
+        self.performer_sub
+            .iter()
+            .map(|sub| Substitute { sub: sub.clone(), for_player: self.performer.clone() })
+            .chain(self.guesser_sub
+                   .iter()
+                   .map(|sub| Substitute { sub: sub.clone(), for_player: self.guesser.clone() }))
+            .chain(self.team_subs.iter().cloned())
+            .collect()
+    }
+
     pub fn with_state(self, state: TurnState) -> Turn {
         Turn { state, ..self }
     }
 
     pub fn as_guessing(self) -> Turn {
         Turn {
-            performer: self.performer,
-            guesser: self.guesser,
             state: TurnState::Guessing(TurnSummary::new()),
+            ..self
         }
     }
 
     pub fn as_ended(self) -> Turn {
+        let substitutes = self.substitutes();
         match self.state {
-            TurnState::Ready => {
+            TurnState::Ready(_) => {
                 Turn {
-                    performer: self.performer,
-                    guesser: self.guesser,
-                    state: TurnState::Ended(TurnSummary::new()),
+                    state: TurnState::Ended(TurnSummary::new().with_substitutes(substitutes)),
+                    ..self
                 }
             },
             TurnState::Guessing(summ) | TurnState::Ended(summ) => {
                 Turn {
-                    performer: self.performer,
-                    guesser: self.guesser,
-                    state: TurnState::Ended(summ)
+                    state: TurnState::Ended(summ.with_substitutes(substitutes)),
+                    ..self
                 }
             }
         }
@@ -96,28 +299,50 @@ impl Turn {
 
     pub fn status(&self) -> String {
         match self.state {
-            TurnState::Ready => 
+            TurnState::Ready(_) => 
                 format!("{} is getting ready to perform to {} who will be guessing", 
-                        &self.performer, 
-                        &self.guesser),
+                        self.acting_performer(), 
+                        self.guessing()),
             TurnState::Guessing(_) =>
                 format!("{} is performing for {} who is guessing",
-                        &self.performer,
-                        &self.guesser),
+                        self.acting_performer(),
+                        self.guessing()),
             TurnState::Ended(_) =>
                 format!("{} has finished performing for {} who was guessing",
-                        &self.performer,
-                        &self.guesser)
+                        self.acting_performer(),
+                        self.guessing())
+        }
+    }
+
+    /// Marks `p` as ready, if the turn hasn't started yet
+    pub fn with_ready(self, p: &Player) -> Turn {
+        match self.state {
+            TurnState::Ready(mut ready) => {
+                if !ready.contains(p) {
+                    ready.push(p.clone());
+                }
+                Turn { state: TurnState::Ready(ready), ..self }
+            },
+            _ => self,
+        }
+    }
+
+    /// Who out of `needed` still hasn't said they're ready. Nobody once the turn's started
+    pub fn waiting_on(&self, needed: Vec<Player>) -> Vec<Player> {
+        match &self.state {
+            TurnState::Ready(ready) => needed.into_iter()
+                .filter(|p| !ready.contains(p))
+                .collect(),
+            _ => vec![],
         }
     }
 
     pub fn with_solved_clue(self, clue: Clue) -> Result<Self, GameError> {
         match self.state {
-            TurnState::Ready | TurnState::Ended(_) => Err(GameError::BadTurnState(self.clone())),
+            TurnState::Ready(_) | TurnState::Ended(_) => Err(GameError::BadTurnState(self.clone())),
             TurnState::Guessing(v) => Ok(Turn {
-                performer: self.performer,
-                guesser: self.guesser,
-                state: TurnState::Guessing(v.with_clue(clue))
+                state: TurnState::Guessing(v.with_clue(clue)),
+                ..self
             })
         }
     }
@@ -0,0 +1,93 @@
+use std::fmt::{Display, Formatter, self};
+use std::str::FromStr;
+use crate::error::Error;
+
+const CELEBRITIES: &[&str] = &[
+    "Albert Einstein", "Beyonce", "Bob Dylan", "Cleopatra", "David Attenborough",
+    "Dolly Parton", "Elvis Presley", "Frida Kahlo", "Gandhi", "Harry Houdini",
+    "Isaac Newton", "Joan of Arc", "Julius Caesar", "Kim Kardashian", "Lady Gaga",
+    "Leonardo da Vinci", "Marie Curie", "Marilyn Monroe", "Michael Jordan", "Muhammad Ali",
+    "Napoleon", "Nelson Mandela", "Oprah Winfrey", "Pablo Picasso", "Queen Elizabeth",
+    "Serena Williams", "Shakespeare", "Taylor Swift", "The Beatles", "W. B. Yeats",
+];
+
+const FILMS: &[&str] = &[
+    "Back to the Future", "Casablanca", "E.T.", "Finding Nemo", "Forrest Gump",
+    "Frozen", "Ghostbusters", "Gone with the Wind", "Home Alone", "Jaws",
+    "Jurassic Park", "King Kong", "Mary Poppins", "Psycho", "Pulp Fiction",
+    "Rocky", "Shrek", "Singin' in the Rain", "Star Wars", "Terminator",
+    "The Godfather", "The Lion King", "The Matrix", "The Sound of Music", "The Wizard of Oz",
+    "Titanic", "Top Gun", "Toy Story", "Up", "Groundhog Day",
+];
+
+const OBJECTS: &[&str] = &[
+    "Accordion", "Anchor", "Boomerang", "Cactus", "Chandelier",
+    "Compass", "Disco ball", "Fire extinguisher", "Garden gnome", "Hammock",
+    "Hot air balloon", "Igloo", "Kettle", "Lava lamp", "Lighthouse",
+    "Microscope", "Parachute", "Pineapple", "Rubber duck", "Sandcastle",
+    "Skateboard", "Snow globe", "Sundial", "Telescope", "Toaster",
+    "Trampoline", "Umbrella", "Volcano", "Wheelbarrow", "Windmill",
+];
+
+const ACTIONS: &[&str] = &[
+    "Baking a cake", "Brushing your teeth", "Building a snowman", "Changing a tyre", "Climbing a mountain",
+    "Conducting an orchestra", "Doing the dishes", "Flying a kite", "Herding sheep", "Ice skating",
+    "Juggling", "Knitting a scarf", "Milking a cow", "Mowing the lawn", "Paddling a canoe",
+    "Parallel parking", "Playing the bagpipes", "Riding a unicycle", "Sneezing", "Surfing",
+    "Taking a selfie", "Tightrope walking", "Walking the dog", "Washing a car", "Waking up late",
+    "Whistling", "Winning an Oscar", "Yodelling", "Zip lining", "Skydiving",
+];
+
+/// The bundled word lists Yeats can top up the bowl from with `!fill-bowl`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Celebrities,
+    Films,
+    Objects,
+    Actions,
+}
+
+impl Category {
+    pub fn all() -> Vec<Category> {
+        vec![
+            Category::Celebrities,
+            Category::Films,
+            Category::Objects,
+            Category::Actions,
+        ]
+    }
+
+    pub fn words(&self) -> &'static [&'static str] {
+        match self {
+            Category::Celebrities => CELEBRITIES,
+            Category::Films => FILMS,
+            Category::Objects => OBJECTS,
+            Category::Actions => ACTIONS,
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Category, Error> {
+        match s.to_lowercase().as_str() {
+            "celebrities" | "celebrity" | "people" => Ok(Category::Celebrities),
+            "films" | "film" | "movies" | "movie" => Ok(Category::Films),
+            "objects" | "object" | "things" => Ok(Category::Objects),
+            "actions" | "action" | "verbs" => Ok(Category::Actions),
+            _ => Err(Error::UnknownCategory(s.to_string())),
+        }
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Celebrities => write!(f, "celebrities"),
+            Category::Films => write!(f, "films"),
+            Category::Objects => write!(f, "objects"),
+            Category::Actions => write!(f, "actions"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod game;
 pub mod error;
 pub mod respond2;
+pub mod clock;
@@ -1,17 +1,19 @@
 use std::collections::HashSet;
-use tokio::time::{Duration, sleep};
+use tokio::time::Duration;
 use async_trait::async_trait;
 use serenity::{
     prelude::*,
     model::prelude::*,
     client::ClientBuilder,
+    collector::ReactionAction,
+    futures::future::BoxFuture,
     framework::standard::{
         Args,
         StandardFramework,
         CommandResult,
         CommandGroup,
         HelpOptions,
-        macros::{command, group, help},
+        macros::{command, group, help, hook},
         help_commands::plain,
     },
 };
@@ -21,10 +23,14 @@ use yeats::{
         game::{
             Game,
             DrawClue,
+            RelayTarget,
+            RelayOutcome,
+            AutoPilot,
         },
         player::Player,
         clue::Clue,
-        turn::Turn,
+        turn::{Turn, TurnState},
+        word_list::Category,
     },
     respond2::{
         Respondable,
@@ -32,6 +38,7 @@ use yeats::{
         OrLog,
         Executor,
         ResponseOk,
+        split_message,
     },
 };
 
@@ -46,20 +53,166 @@ async fn status(ctx: &Context, msg: &Message) -> CommandResult {
         .await
 }
 
-/// Reset the game back to nothing - WARNING clears all clues and players
+/// How long the host has to confirm a `!reset`
+const RESET_CONFIRM_SECS: u64 = 30;
+
+/// Roles of the message's author, empty for direct messages
+fn member_roles(msg: &Message) -> Vec<RoleId> {
+    msg.member
+        .as_ref()
+        .map(|m| m.roles.clone())
+        .unwrap_or_default()
+}
+
+/// Reset the game back to nothing - WARNING clears all clues and players. Only the host can
+/// do this, and they have to react 👍 to confirm it. If you just want to play again with the
+/// same players and clues use `!rematch` instead
 #[command]
 async fn reset(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
     Executor::new(ctx, msg)
-        .write(|g| {
+        .try_get(|g| g.check_host(&by, &roles))
+        .await
+        .or_send()
+        .await?;
+
+    let prompt = msg.reply(ctx, format!(
+            "This will throw away all the players and clues, react 👍 within {} seconds if you really want to reset",
+            RESET_CONFIRM_SECS))
+        .await?;
+    prompt.react(ctx, '👍').await?;
+    let confirmed = prompt.await_reaction(ctx)
+        .author_id(msg.author.id.0)
+        .timeout(Duration::from_secs(RESET_CONFIRM_SECS))
+        .await
+        .map(|action| match action.as_ref() {
+            ReactionAction::Added(r) => r.emoji == ReactionType::from('👍'),
+            ReactionAction::Removed(_) => false,
+        })
+        .unwrap_or(false);
+    if !confirmed {
+        log::info!("{} didn't confirm the reset", msg.author.name);
+        msg.reply(ctx, "Phew, not resetting then").await?;
+        return Ok(());
+    }
+
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
             log::info!("{} reset the game", msg.author.name);
             g.reset();
-            ResponseOk::new(ctx, msg)
-                .with_react('👍')})
+            Ok(ResponseOk::new(ctx, msg)
+                .with_react('👍'))})
         .await
         .send()
         .await
 }
 
+/// Play again with the same players and clues. All the clues go back in the bowl and
+/// the settings go back to normal. Only the host can do this
+#[command]
+async fn rematch(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            log::info!("{} started a rematch", msg.author.name);
+            g.rematch();
+            Ok(ResponseOk::new(ctx, msg)
+               .with_content(g.status()))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Become the host of the game. The first person to join is the host until someone
+/// else runs this. Only the host can reset, start or advance the game, kick players
+/// and change the settings
+#[command]
+async fn host(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.claim_host(by)?;
+            log::info!("{} is now hosting", msg.author.name);
+            Ok(ResponseOk::new(ctx, msg)
+               .with_content(format!("{} is now the host", msg.author.name)))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Kick the mentioned players out of the game before it starts. Only the host can do this
+#[command]
+async fn kick(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let kicked: Vec<Player> = msg.mentions
+        .iter()
+        .map(Player::from)
+        .collect();
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            if kicked.is_empty() {
+                return Err(Error::BadArguments("!kick @player".to_string()));
+            }
+            for p in kicked.iter() {
+                g.kick_player(p)?;
+                log::info!("{} kicked {}", msg.author.name, p.name);
+            }
+            Ok(ResponseOk::new(ctx, msg)
+               .with_content(format!("Kicked {}", kicked.iter()
+                                     .map(|p| p.name.clone())
+                                     .collect::<Vec<_>>()
+                                     .join(", "))))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Show the game's settings with `!config`, or change one with `!config <key> <value>`.
+/// Only the host can change settings
+#[command]
+async fn config(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    if args.is_empty() {
+        return Executor::new(ctx, msg)
+            .read(|g| ResponseOk::new(ctx, msg)
+                  .with_content(format!("Settings:\n```\n{}\n```", g.settings)))
+            .await
+            .send()
+            .await
+    }
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let key = args.single::<String>()
+        .map_err(|_| Error::BadArguments("!config <key> <value>".to_string()));
+    let value = args.rest().to_string();
+    // Turning auto-pilot on mid-game should get things moving straight away
+    let is_auto_pilot = matches!(&key, Ok(k) if k == "auto-pilot");
+    let response = Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let key = key?;
+            g.configure(&key, &value)?;
+            log::info!("{} set {} to {}", msg.author.name, key, value);
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
+        })
+        .await;
+    let configured = response.is_ok();
+    response.send().await?;
+    if configured && is_auto_pilot {
+        auto_pilot(ctx, msg).await?;
+    }
+    Ok(())
+}
+
 /// Join the game
 #[command]
 async fn join(ctx: &Context, msg: &Message) -> CommandResult {
@@ -112,12 +265,43 @@ async fn add_clue(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     }
 }
 
+/// Tops up the bowl with clues from Yeats' own word lists, handy when there's only
+/// a few of you playing. Usage: `!fill-bowl <n> [celebrities|films|objects|actions]`,
+/// leave out the category to draw from all of them. Only the host can do this
+#[command]
+#[aliases("fill-bowl")]
+async fn fill_bowl(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let usage = "!fill-bowl <n> [celebrities|films|objects|actions]";
+    let n = args.single::<usize>()
+        .map_err(|_| Error::BadArguments(usage.to_string()));
+    let category = match args.single::<String>() {
+        Ok(c) => c.parse::<Category>().map(Some),
+        Err(_) => Ok(None),
+    };
+    let bot: Player = (&User::from(ctx.cache.current_user().await)).into();
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let added = g.fill_bowl(n?, category?, &bot)?;
+            log::info!("{} added {} generated clues to the bowl", msg.author.name, added);
+            Ok(ResponseOk::new(ctx, msg)
+               .with_content(format!("Added {} clues from my word lists to the bowl", added)))
+        })
+        .await
+        .send()
+        .await
+}
+
 /// Starts the game. After the game as started no more players can join
-/// nor can clues be added to the bowl.
+/// nor can clues be added to the bowl. Only the host can do this
 #[command]
 #[aliases("start-game")]
 async fn start_game(ctx: &Context, msg: &Message) -> CommandResult {
     log::info!("Starting game");
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
 
     let channel = msg.channel(ctx)
         .await
@@ -126,6 +310,7 @@ async fn start_game(ctx: &Context, msg: &Message) -> CommandResult {
 
     Executor::new(ctx, msg)
         .try_write(|g| {
+            g.check_host(&by, &roles)?;
             let channel = channel?;
             g.start_game(channel.clone())?;
             Ok(ResponseOk::new(ctx, msg)
@@ -134,7 +319,8 @@ async fn start_game(ctx: &Context, msg: &Message) -> CommandResult {
         })
         .await
         .send()
-        .await
+        .await?;
+    auto_pilot(ctx, msg).await
 }
 
 /// Gets the next turn ready and tags the players involved so they no to 
@@ -142,27 +328,22 @@ async fn start_game(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 #[aliases("next-turn")]
 async fn next_turn(ctx: &Context, msg: &Message) -> CommandResult {
-    let (turn, channel) = Executor::new(ctx, msg)
+    let turn = Executor::new(ctx, msg)
         .try_write_and_get(|g| {
-            let channel = g.main_channel
+            g.main_channel
                 .clone()
                 .ok_or(Error::NoChannel)?;
-            let turn = g.prepare_turn()?;
-            Ok((turn, channel))
+            g.prepare_turn()
         })
         .await
         .or_send()
         .await?;
     log::debug!("{:?}", &turn);
-    ResponseOk::new(ctx, msg)
-        .with_channel(channel)
-        .with_content(format!(
-            "Get ready! {} will be performing for {}",
-            turn.performer,
-            turn.guesser
-            ))
-        .send()
-        .await
+    match get_ready(ctx, msg, &turn, String::new()).await? {
+        Readiness::Started(turn, round_number) => run_turn(ctx, msg, turn, round_number).await,
+        Readiness::Skipped => auto_pilot(ctx, msg).await,
+        Readiness::NotYet => Ok(()),
+    }
 }
 
 /// Starts the timer and draws the first clue for the performer. Clues are DM'd
@@ -172,71 +353,351 @@ async fn next_turn(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 #[aliases("start-turn")]
 async fn start_turn(ctx: &Context, msg: &Message) -> CommandResult {
-    let (Turn { performer, guesser, .. }, round_number) = Executor::new(ctx, msg)
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let (turn, round_number) = Executor::new(ctx, msg)
         .try_write_and_get(|g| {
-            g.start_turn()
+            // The host can start a turn even if someone hasn't said they're ready
+            match g.start_turn() {
+                Err(Error::NotReady(_)) if g.check_host(&by, &roles).is_ok() => g.force_start_turn(),
+                started => started,
+            }
         })
     .await
     .or_send()
     .await?;
-    // Send a clue
-    
-    let dm_chan = performer.user
-        .create_dm_channel(ctx)
+    run_turn(ctx, msg, turn, round_number).await
+}
+
+/// Say you're ready for your turn, when there's a ready check. The turn starts once
+/// everyone it's waiting on is
+#[command]
+async fn ready(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let started = Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.ready(&by))
         .await
-        .or_else(|e| {
-            log::warn!("{}", &e);
-            Err(e)
-        })?;
-    
+        .or_send()
+        .await?;
+    msg.react(ctx, '👍').await?;
+    match started {
+        Some((turn, round_number)) => run_turn(ctx, msg, turn, round_number).await,
+        None => Ok(()),
+    }
+}
+
+/// Come back after sitting out for missing your turns. You'll be in from the next round
+#[command]
+async fn back(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
     Executor::new(ctx, msg)
         .try_write(|g| {
-            let DrawClue { clue, .. } = g.draw_clue(&performer)?;
-            if let Some(clue) = clue {
-                Ok(ResponseOk::new(ctx, msg)
-                    .with_dm_channel(dm_chan)
-                    .with_content(format!("Your clue is:\n{}", clue)))
-            } else {
-                // Not sure we should ever get to this state, maybe
-                // we should deliberately error if we do
-                g.end_turn(&performer, &guesser, round_number)?;
-                let reply = g.turn_summary()?.to_string();
-                let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
-                Ok(ResponseOk::new(ctx, msg)
-                    .with_channel(channel)
-                    .with_content(format!("Turn's over because the bowl is empty. Well done {} and {}, you solved the following clues:\n{}",
-                                          &performer,
-                                          &guesser,
-                                          reply))
-                    .with_redact_after(20))
-            }
+            g.back(&by)?;
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
         })
         .await
         .send()
-        .await?;
+        .await
+}
 
-    log::info!("Starting timer for {} -> {}", &performer, &guesser);
-    sleep(Duration::from_secs(50)).await;
+/// Give the current turn more time, e.g. `!add-time 30`, or take some away with a negative
+/// number. Only the host can do this
+#[command]
+#[aliases("add-time")]
+async fn add_time(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let secs = args.single::<i64>()
+        .map_err(|_| Error::BadArguments("!add-time <seconds>".to_string()));
     Executor::new(ctx, msg)
-        .try_read(|g| {
-            let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let left = g.add_time(secs?)?;
+            log::info!("{} changed the time, {}s left", msg.author.name, left);
+            Ok(ResponseOk::new(ctx, msg)
+               .with_content(format!("{} seconds left", left)))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// End the current turn now, just as if time had run out. Only the host can do this
+#[command]
+#[aliases("end-turn")]
+async fn end_turn(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            g.end_turn_early()?;
+            log::info!("{} ended the turn", msg.author.name);
             Ok(ResponseOk::new(ctx, msg)
-               .with_content("TEN SECONDS LEFT!!".to_string())
-               .with_channel(channel))
+               .with_react('👍'))
         })
         .await
         .send()
+        .await
+}
+
+/// How long the ready check waits before calling in the host
+const READY_TIMEOUT_SECS: u64 = 60;
+
+/// How things stand once `get_ready` is done waiting
+enum Readiness {
+    /// Everyone's ready and the turn's started
+    Started(Turn, i64),
+    /// The performer never showed so the turn was skipped
+    Skipped,
+    /// Still waiting, or someone else has taken it from here
+    NotYet,
+}
+
+/// What `get_ready` does if the turn still hasn't started after a while
+enum ReadyTimeout {
+    PingHost,
+    RemindPerformer,
+    Skip,
+}
+
+/// Posts the "get ready" message for a turn. If there's a ready check it says who it's
+/// waiting on, and they can react ✅ to it instead of typing `!ready`. If the last of them
+/// reacts, the turn starts and is returned. If they're not all ready in time the host is
+/// pinged, and if the turn's still not started after the AFK timeout the performer is
+/// reminded, then skipped
+async fn get_ready(ctx: &Context, msg: &Message, turn: &Turn, intro: String) -> CommandResult<Readiness> {
+    let (channel, waiting, clock, afk_timeout) = Executor::new(ctx, msg)
+        .try_get(|g| {
+            let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
+            Ok((channel, g.waiting_on()?, g.clock.clone(), g.settings.afk_timeout))
+        })
+        .await
+        .or_send()
+        .await?;
+    let (performer, guesser) = (turn.acting_performer(), turn.guessing());
+    let mut content = format!("{}Get ready! {} will be performing for {}", intro, performer, guesser);
+    if !waiting.is_empty() {
+        content.push_str(&format!(
+            "\n{}, react ✅ or type `!ready` when you're ready",
+            waiting.iter()
+                .map(|p| p.user.to_string())
+                .collect::<Vec<_>>()
+                .join(" and ")));
+    }
+    let prompt = channel.send_message(ctx, |m| m.content(&content)).await?;
+    if !waiting.is_empty() {
+        prompt.react(ctx, '✅').await?;
+    }
+
+    let mut timeouts = vec![];
+    if !waiting.is_empty() {
+        timeouts.push((READY_TIMEOUT_SECS, ReadyTimeout::PingHost));
+    }
+    if afk_timeout > 0 {
+        timeouts.push((afk_timeout, ReadyTimeout::RemindPerformer));
+        timeouts.push((2 * afk_timeout, ReadyTimeout::Skip));
+    }
+    timeouts.sort_by_key(|(secs, _)| *secs);
+
+    // Whether this turn is still the one waiting to start, and who on. Someone might have
+    // stood in since, so it's the turn as it is now
+    let still_waiting = || async {
+        Executor::new(ctx, msg)
+            .get(|g| g.current_turn()
+                 .ok()
+                 .filter(|t| t.performer == turn.performer && t.guesser == turn.guesser)
+                 .filter(|t| matches!(t.state, TurnState::Ready(_)))
+                 .map(|t| (t.clone(), g.waiting_on().unwrap_or_default())))
+            .await
+            .ok()
+            .flatten()
+    };
+    let mut waited = 0;
+    for (secs, timeout) in timeouts {
+        let wait = clock.sleep(Duration::from_secs(secs - waited));
+        tokio::pin!(wait);
+        waited = secs;
+        loop {
+            let reaction = tokio::select! {
+                _ = &mut wait => break,
+                reaction = prompt.await_reaction(ctx) => reaction,
+            };
+            let user_id = match reaction.as_deref() {
+                Some(ReactionAction::Added(r)) if r.emoji == ReactionType::from('✅') => r.user_id,
+                _ => None,
+            };
+            let started = match user_id {
+                Some(user_id) => Executor::new(ctx, msg)
+                    .try_write_and_get(|g| {
+                        let by = g.find_player(user_id)?;
+                        g.ready(&by)
+                    })
+                    .await,
+                None => continue,
+            };
+            match started {
+                Ok(Some((turn, round_number))) => return Ok(Readiness::Started(turn, round_number)),
+                Ok(None) => (),
+                // Maybe someone else started it, or it's a reaction from someone who doesn't need to be ready
+                Err(_) => if still_waiting().await.is_none() {
+                    return Ok(Readiness::NotYet);
+                },
+            }
+        }
+
+        let (turn, waiting) = match still_waiting().await {
+            Some(still) => still,
+            None => return Ok(Readiness::NotYet),
+        };
+        let (performer, guesser) = (turn.acting_performer(), turn.guessing());
+        match timeout {
+            ReadyTimeout::PingHost if !waiting.is_empty() => {
+                log::info!("Ready check for {} -> {} timed out", performer, guesser);
+                let host = Executor::new(ctx, msg)
+                    .get(|g| g.host.clone())
+                    .await
+                    .or_log()?
+                    .map(|h| format!("{}, ", h.user))
+                    .unwrap_or_default();
+                channel.send_message(ctx, |m| m.content(format!(
+                            "{}still waiting on {} to be ready. The host can `!start-turn` to start without them",
+                            host,
+                            waiting.iter()
+                                .map(|p| p.name.clone())
+                                .collect::<Vec<_>>()
+                                .join(" and "))))
+                    .await?;
+            },
+            ReadyTimeout::PingHost => (),
+            ReadyTimeout::RemindPerformer => {
+                let reminder = format!(
+                    "{}, it's your turn to perform for {}! If it doesn't start in the next {} seconds it'll be skipped",
+                    performer.user,
+                    guesser,
+                    afk_timeout);
+                channel.send_message(ctx, |m| m.content(&reminder)).await?;
+                performer.user
+                    .direct_message(ctx, |m| m.content(&reminder))
+                    .await?;
+            },
+            ReadyTimeout::Skip => {
+                let (sitting_out, afk_skips) = Executor::new(ctx, msg)
+                    .try_write_and_get(|g| Ok((g.skip_turn()?, g.settings.afk_skips)))
+                    .await
+                    .or_log()?;
+                log::info!("Skipped {}'s turn", performer);
+                let content = if turn.performer_sub.is_some() {
+                    format!("{} didn't start the turn they were standing in for, so it's back with {} at the back of the queue",
+                            performer.name,
+                            turn.performer.name)
+                } else if sitting_out {
+                    format!("{} has missed {} turns in a row, so they're sitting out until they come `!back`",
+                            performer.name,
+                            afk_skips)
+                } else {
+                    format!("{} didn't start their turn, so it's been moved to the back of the queue",
+                            performer.name)
+                };
+                channel.send_message(ctx, |m| m.content(content)).await?;
+                return Ok(Readiness::Skipped);
+            },
+        }
+    }
+    Ok(Readiness::NotYet)
+}
+
+/// Plays a turn that's just started, then lets auto-pilot take it from there if it's on
+async fn run_turn(ctx: &Context, msg: &Message, turn: Turn, round_number: i64) -> CommandResult {
+    let played = play_turn(ctx, msg, turn, round_number).await;
+    auto_pilot(ctx, msg).await?;
+    played
+}
+
+/// Boxed, so that `send_clue` can start a carried over turn even though playing a turn
+/// sends clues
+fn run_carried_turn<'a>(ctx: &'a Context, msg: &'a Message, turn: Turn, round_number: i64) -> BoxFuture<'a, CommandResult> {
+    Box::pin(run_turn(ctx, msg, turn, round_number))
+}
+
+/// Runs a turn that's just been started: draws the first clue, times the turn and posts
+/// the recap when time's up
+async fn play_turn(ctx: &Context, msg: &Message, turn: Turn, round_number: i64) -> CommandResult {
+    // Who's actually playing, which isn't who the turn belongs to if someone's standing in
+    let (acting_performer, guessing) = (turn.acting_performer().clone(), turn.guessing());
+    let Turn { performer, guesser, .. } = turn;
+    // Send a clue
+    let draw = Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.draw_first_clue())
+        .await
+        .or_send()
+        .await?;
+    send_clue(ctx, msg, draw).await?;
+
+    let (timer, grace) = Executor::new(ctx, msg)
+        .try_get(|g| Ok((g.timer.clone().ok_or(Error::CurrentTurnNotYetStarted)?, g.settings.grace)))
+        .await
+        .or_send()
         .await?;
-    sleep(Duration::from_secs(10)).await;
-    log::info!("Times up for {} -> {}", &performer, &guesser);
+    log::info!("Starting timer for {} -> {}", &acting_performer, &guessing);
+    // Goes round again if the host adds time after the warning
+    loop {
+        if timer.warning().await {
+            Executor::new(ctx, msg)
+                .try_read(|g| {
+                    if !g.is_playing(&performer, &guesser, round_number) {
+                        // The bowl ran out before time did
+                        return Ok(ResponseOk::new(ctx, msg));
+                    }
+                    let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
+                    Ok(ResponseOk::new(ctx, msg)
+                       .with_content("TEN SECONDS LEFT!!".to_string())
+                       .with_channel(channel))
+                })
+                .await
+                .send()
+                .await?;
+        }
+        if timer.times_up().await {
+            break;
+        }
+    }
+    log::info!("Times up for {} -> {}", &acting_performer, &guessing);
+
+    if grace > 0 {
+        let in_grace = Executor::new(ctx, msg)
+            .try_write_and_get(|g| {
+                g.start_grace(&performer, &guesser, round_number)?;
+                g.main_channel.clone().ok_or(Error::NoChannel)
+            })
+            .await;
+        // Otherwise the bowl ran out first
+        if let Ok(channel) = in_grace {
+            ResponseOk::new(ctx, msg)
+                .with_channel(channel)
+                .with_content(format!("Time's up! {}, you've got {} seconds to claim that last clue", &acting_performer, grace))
+                .send()
+                .await?;
+            timer.grace_over().await;
+        }
+    }
 
-    let reply: String = Executor::new(ctx, msg)
+    let reply: Option<String> = Executor::new(ctx, msg)
         .try_write_and_get(|g| {
+            if !g.is_playing(&performer, &guesser, round_number) {
+                return Ok(None);
+            }
             g.end_turn(&performer, &guesser, round_number)
-                .and_then(|()| g.turn_summary().map(|s| s.to_string()))
+                .and_then(|()| g.turn_summary().map(|s| Some(s.to_string())))
         })
         .await
         .or_log()?;
+    let reply = match reply {
+        Some(reply) => reply,
+        // The bowl ran out first, and that's already been recapped
+        None => return Ok(()),
+    };
 
     Executor::new(ctx, msg)
         .try_read(|g| {
@@ -244,8 +705,8 @@ async fn start_turn(ctx: &Context, msg: &Message) -> CommandResult {
             Ok(ResponseOk::new(ctx, msg)
                 .with_channel(channel)
                 .with_content(format!("Time's up! {} and {}, you solved the following clues:\n{}",
-                                      &performer,
-                                      &guesser,
+                                      &acting_performer,
+                                      &guessing,
                                       reply))
                 .with_redact_after(20))
         })
@@ -254,40 +715,435 @@ async fn start_turn(ctx: &Context, msg: &Message) -> CommandResult {
         .await
 }
 
+/// If auto-pilot is on, keeps the game going after a turn: gets the next turn ready, counts
+/// down, starts it and moves on to the next round when the bowl's empty, until the game's
+/// over. Stops quietly if someone starts a turn by hand during the countdown, whoever did
+/// takes over from there
+async fn auto_pilot(ctx: &Context, msg: &Message) -> CommandResult {
+    loop {
+        let next = Executor::new(ctx, msg)
+            .try_write_and_get(|g| g.auto_pilot_next())
+            .await
+            .or_log()?;
+        let (turn, new_round) = match next {
+            AutoPilot::Off => return Ok(()),
+            AutoPilot::GameOver => {
+                return Executor::new(ctx, msg)
+                    .try_read(|g| {
+                        let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
+                        Ok(ResponseOk::new(ctx, msg)
+                            .with_channel(channel)
+                            .with_content(g.status()))
+                    })
+                    .await
+                    .send()
+                    .await;
+            },
+            AutoPilot::NextTurn { turn, new_round } => (turn, new_round),
+        };
+        let (channel, clock, countdown, waiting) = Executor::new(ctx, msg)
+            .try_get(|g| {
+                let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
+                Ok((channel, g.clock.clone(), g.settings.countdown, g.waiting_on()?))
+            })
+            .await
+            .or_log()?;
+        let round_intro = new_round
+            .map(|n| format!("That's the bowl empty, on to round {}!\n", n))
+            .unwrap_or_default();
+        let started = if waiting.is_empty() {
+            ResponseOk::new(ctx, msg)
+                .with_channel(channel)
+                .with_content(format!(
+                    "{}Get ready! {} will be performing for {}, starting in {} seconds",
+                    round_intro,
+                    turn.acting_performer(),
+                    turn.guessing(),
+                    countdown
+                    ))
+                .send()
+                .await?;
+            clock.sleep(Duration::from_secs(countdown)).await;
+            Executor::new(ctx, msg)
+                .try_write_and_get(|g| {
+                    if g.settings.auto_pilot {
+                        g.start_turn().map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                })
+                .await
+                .ok()
+                .flatten()
+                .map(|(turn, round_number)| Readiness::Started(turn, round_number))
+                .unwrap_or(Readiness::NotYet)
+        } else {
+            get_ready(ctx, msg, &turn, round_intro).await?
+        };
+        match started {
+            Readiness::Started(turn, round_number) => {
+                if let Err(e) = play_turn(ctx, msg, turn, round_number).await {
+                    log::warn!("{}", e);
+                }
+            },
+            Readiness::Skipped => (),
+            Readiness::NotYet => return Ok(()),
+        }
+    }
+}
+
+async fn dm_clue(ctx: &Context, msg: &Message, performer: &Player, content: String) -> CommandResult {
+    let dm_chan = performer.user
+        .create_dm_channel(ctx)
+        .await
+        .or_else(|e| {
+            log::warn!("{}", &e);
+            Err(e)
+        })?;
+    ResponseOk::new(ctx, msg)
+        .with_dm_channel(dm_chan)
+        .with_content(content)
+        .send()
+        .await
+}
+
+/// With a clue limit set, swaps the performer's clue for another whenever they've been stuck
+/// on it too long. Stops once the clue's been solved, it'll have a watcher of its own
+async fn watch_clue(ctx: &Context, msg: &Message) -> CommandResult {
+    loop {
+        let (clock, clue_limit) = Executor::new(ctx, msg)
+            .get(|g| (g.clock.clone(), g.settings.clue_limit))
+            .await
+            .or_log()?;
+        if clue_limit == 0 {
+            return Ok(());
+        }
+        clock.sleep(Duration::from_secs(clue_limit)).await;
+        let draw = Executor::new(ctx, msg)
+            .try_write_and_get(|g| g.return_stale_clue())
+            .await
+            .ok()
+            .flatten();
+        match draw {
+            Some(DrawClue { clue: Some(clue), performer, .. }) => {
+                dm_clue(ctx, msg, &performer, format!("Out of time on that one, it's gone back in the bowl. Your next clue is:\n{}", clue)).await?;
+            },
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// DMs the performer their clue, or if the bowl has run out ends the turn and posts the recap
+async fn send_clue(ctx: &Context, msg: &Message, draw: DrawClue) -> CommandResult {
+    let DrawClue { clue, performer, guesser, last_clue } = draw;
+    if last_clue {
+        ResponseOk::new(ctx, msg)
+            .with_react('⏱')
+            .with_content("Just in time! That's the end of your turn".to_string())
+            .send()
+            .await
+    } else if let Some(clue) = clue {
+        dm_clue(ctx, msg, &performer, format!("Your clue is:\n{}", clue)).await?;
+        let (ctx, msg) = (ctx.clone(), msg.clone());
+        tokio::spawn(async move {
+            if let Err(e) = watch_clue(&ctx, &msg).await {
+                log::warn!("{}", e);
+            }
+        });
+        Ok(())
+    } else {
+        let (summary, carried, channel) = Executor::new(ctx, msg)
+            .try_write_and_get(|g| {
+                let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
+                let (summary, carried) = g.bowl_emptied()?;
+                Ok((summary, carried, channel))
+            })
+            .await
+            .or_send()
+            .await?;
+        ResponseOk::new(ctx, msg)
+            .with_channel(channel.clone())
+            .with_content(format!("Turn's over because the bowl is empty. Well done {} and {}, you solved the following clues:\n{}",
+                                  &performer,
+                                  &guesser,
+                                  summary))
+            .with_redact_after(20)
+            .send()
+            .await?;
+        match carried {
+            Some((turn, round_number)) => {
+                ResponseOk::new(ctx, msg)
+                    .with_channel(channel)
+                    .with_content(format!("On to round {}! {} carries on for {} with the {} seconds they had left",
+                                          round_number,
+                                          turn.acting_performer(),
+                                          turn.guessing(),
+                                          turn.time_left.unwrap_or_default()))
+                    .send()
+                    .await?;
+                run_carried_turn(ctx, msg, turn, round_number).await
+            },
+            None => Ok(()),
+        }
+    }
+}
+
 /// Draws the next clue if you're the current performer, otherwise tells you to piss off.
+/// If the host has turned on `guesser-confirms`, it's the guesser who has to `!got-it` instead
+/// (DM or in channel), and the performer is sent the next one
 #[command]
-#[aliases("next-clue", "y", "Y")]
+#[aliases("next-clue", "y", "Y", "got-it")]
 async fn next_clue(ctx: &Context, msg: &Message) -> CommandResult {
     let by: Player = (&msg.author).into();
-    let dm_chan = by.user
+    let draw = Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.draw_clue(&by))
+        .await
+        .or_send()
+        .await?;
+    send_clue(ctx, msg, draw).await
+}
+
+/// If the guesser `!got-it` a clue they didn't really get, the performer can dispute
+/// it and the clue is shown to them again
+#[command]
+async fn dispute(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let draw = Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.dispute_clue(&by))
+        .await
+        .or_send()
+        .await?;
+    log::info!("{} disputed a clue", &by);
+    let dm_chan = draw.guesser.user
+        .create_dm_channel(ctx)
+        .await
+        .or_else(|e| {
+            log::warn!("{}", &e);
+            Err(e)
+        })?;
+    ResponseOk::new(ctx, msg)
+        .with_dm_channel(dm_chan)
+        .with_content(format!("{} disputed that one, keep guessing!", draw.performer.name))
+        .send()
+        .await?;
+    send_clue(ctx, msg, draw).await
+}
+
+/// Takes back the last clue you said was solved (DM or in channel), if you hit `y` by
+/// mistake, and it's sent to you again. Works for the last few clues of your turn, and
+/// the host can do it too
+#[command]
+async fn undo(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let draw = Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.undo(&by, &roles))
+        .await
+        .or_send()
+        .await?;
+    log::info!("{} undid a clue", &by);
+    msg.react(ctx, '↩').await?;
+    send_clue(ctx, msg, draw).await
+}
+
+/// DMs the host this round's solved clues, and the ones still in the bowl that somebody's
+/// already seen, each with a code for `!mark-solved` and `!mark-unsolved`. Only the host can
+/// do this, between turns
+#[command]
+async fn clues(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let (solved, seen) = Executor::new(ctx, msg)
+        .try_get(|g| {
+            g.check_host(&by, &roles)?;
+            g.correctable_clues()
+        })
+        .await
+        .or_send()
+        .await?;
+    let list = |clues: &[(usize, Clue)]| match clues {
+        [] => "nothing yet".to_string(),
+        _ => clues.iter()
+            .map(|(code, c)| format!("`{}` {}", code, c.text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    let dm_chan = msg.author
         .create_dm_channel(ctx)
         .await
         .or_else(|e| {
             log::warn!("{}", &e);
             Err(e)
         })?;
-    
+    let text = format!("Solved this round:\n{}\n\nSeen but still in the bowl:\n{}", list(&solved), list(&seen));
+    for part in split_message(&text) {
+        ResponseOk::new(ctx, msg)
+            .with_dm_channel(dm_chan.clone())
+            .with_content(part)
+            .send()
+            .await?;
+    }
+    msg.react(ctx, '📬').await?;
+    Ok(())
+}
+
+/// Counts a clue as solved after all, by its code from `!clues`. Only the host can do this,
+/// between turns
+#[command]
+#[aliases("mark-solved")]
+async fn mark_solved(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let code = args.single::<usize>()
+        .map_err(|_| Error::BadArguments("!mark-solved <code>".to_string()));
     Executor::new(ctx, msg)
         .try_write(|g| {
-            let DrawClue { clue, performer, guesser } = g.draw_clue(&by)?;
-            if let Some(clue) = clue {
-                Ok(ResponseOk::new(ctx, msg)
-                    .with_dm_channel(dm_chan)
-                    .with_content(format!("Your clue is:\n{}", clue)))
-            } else {
-                let round_number = g.current_round_number()
-                    .ok_or(Error::NoRound)?;
-                g.end_turn(&performer, &guesser, round_number)?;
-                let reply = g.turn_summary()?.to_string();
-                let channel = g.main_channel.clone().ok_or(Error::NoChannel)?;
-                Ok(ResponseOk::new(ctx, msg)
-                    .with_channel(channel)
-                    .with_content(format!("Turn's over because the bowl is empty. Well done {} and {}, you solved the following clues:\n{}",
-                                          &performer,
-                                          &guesser,
-                                          reply))
-                    .with_redact_after(20))
-            }
+            g.check_host(&by, &roles)?;
+            let clue = g.mark_solved(code?)?;
+            log::info!("{} marked {} as solved", msg.author.name, &clue);
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Puts a clue that was counted as solved back in the bowl, by its code from `!clues`. Only
+/// the host can do this, between turns
+#[command]
+#[aliases("mark-unsolved")]
+async fn mark_unsolved(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let code = args.single::<usize>()
+        .map_err(|_| Error::BadArguments("!mark-unsolved <code>".to_string()));
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let clue = g.mark_unsolved(code?)?;
+            log::info!("{} marked {} as unsolved", msg.author.name, &clue);
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Shows who's up next, in the order the turns will be played
+#[command]
+async fn queue(ctx: &Context, msg: &Message) -> CommandResult {
+    Executor::new(ctx, msg)
+        .try_read(|g| {
+            let upcoming = g.queue()?
+                .iter()
+                .enumerate()
+                .map(|(i, t)| format!("{}. {} → {}", i + 1, t.acting_performer().name, t.guessers()
+                                     .iter()
+                                     .map(|g| g.name.clone())
+                                     .collect::<Vec<_>>()
+                                     .join(", ")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(ResponseOk::new(ctx, msg)
+               .with_content(format!("Up next:\n{}", upcoming)))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Moves a turn to a different place in the queue, e.g. `!move-turn 3 1` makes the third
+/// turn in `!queue` the next one. Only the host can do this
+#[command]
+#[aliases("move-turn")]
+async fn move_turn(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let positions = args.single::<usize>()
+        .and_then(|from| args.single::<usize>().map(|to| (from, to)))
+        .map_err(|_| Error::BadArguments("!move-turn <from> <to>".to_string()));
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let (from, to) = positions?;
+            g.move_turn(from, to)?;
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Swaps two turns in the queue, e.g. `!swap-turns 1 2`. Only the host can do this
+#[command]
+#[aliases("swap-turns")]
+async fn swap_turns(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let positions = args.single::<usize>()
+        .and_then(|a| args.single::<usize>().map(|b| (a, b)))
+        .map_err(|_| Error::BadArguments("!swap-turns <a> <b>".to_string()));
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let (a, b) = positions?;
+            g.swap_turns(a, b)?;
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Changes who performs for whom in a queued turn, e.g. `!set-turn 2 @alice @bob` has
+/// alice perform for bob in the second turn. Only the host can do this
+#[command]
+#[aliases("set-turn")]
+async fn set_turn(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let usage = "!set-turn <n> @performer @guesser";
+    let position = args.single::<usize>()
+        .map_err(|_| Error::BadArguments(usage.to_string()));
+    let pair = args.single::<UserId>()
+        .and_then(|p| args.single::<UserId>().map(|g| (p, g)))
+        .map_err(|_| Error::BadArguments(usage.to_string()));
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            g.check_host(&by, &roles)?;
+            let (performer, guesser) = pair?;
+            g.set_turn(position?, performer, guesser)?;
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
+        })
+        .await
+        .send()
+        .await
+}
+
+/// Has someone stand in for a player in their next turn, e.g. `!sub @alice for @bob` has
+/// alice perform or guess instead of bob, just for the one turn. Anyone can find someone
+/// to stand in for them, otherwise only the host can do this
+#[command]
+async fn sub(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
+    let players = args.single::<UserId>()
+        .ok()
+        .filter(|_| args.single::<String>().map(|w| w == "for").unwrap_or(false))
+        .and_then(|sub| args.single::<UserId>().ok().map(|for_player| (sub, for_player)))
+        .ok_or_else(|| Error::BadArguments("!sub @player for @player".to_string()));
+    Executor::new(ctx, msg)
+        .try_write(|g| {
+            let (sub, for_player) = players?;
+            let turn = g.substitute(&by, &roles, sub, for_player)?;
+            log::info!("Substituted, that turn is now {} -> {}", turn.acting_performer(), turn.guessing());
+            Ok(ResponseOk::new(ctx, msg)
+               .with_react('👍'))
         })
         .await
         .send()
@@ -296,12 +1152,16 @@ async fn next_clue(ctx: &Context, msg: &Message) -> CommandResult {
 
 /// Once the bowl has run out of clues, it's time for the next round. All the clues
 /// are put back into the bowl and the turn order (as well as the performer/guesser pairs)
-/// are shuffled. If it's not time to start a new round you'll be told so.
+/// are shuffled. If it's not time to start a new round you'll be told so. Only the
+/// host can do this
 #[command]
 #[aliases("next-round")]
 async fn next_round(ctx: &Context, msg: &Message) -> CommandResult {
+    let by: Player = (&msg.author).into();
+    let roles = member_roles(msg);
     Executor::new(ctx, msg)
         .try_write(|g| {
+            g.check_host(&by, &roles)?;
             g.advance_game()?;
             Ok(ResponseOk::new(ctx, msg)
                .with_content(g.status()))
@@ -311,6 +1171,97 @@ async fn next_round(ctx: &Context, msg: &Message) -> CommandResult {
         .await
 }
 
+/// In relay mode, posts what the performer DMs to the guesser or the main channel. If it
+/// breaks the round's rules it isn't passed on, and the performer's told why. Returns whether
+/// the message was the performer's to relay at all
+async fn relay(ctx: &Context, msg: &Message, by: &Player) -> CommandResult<bool> {
+    let outcome = match Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.relay(by, &msg.content))
+        .await {
+        Ok(outcome) => outcome,
+        Err(_) => return Ok(false),
+    };
+    let response = || ResponseOk::new(ctx, msg)
+        .with_content(format!("**{}:** {}", by.name, msg.content));
+    match outcome {
+        RelayOutcome::Relayed(RelayTarget::Guessers(guessers)) => {
+            for guesser in guessers {
+                let dm_chan = guesser.user
+                    .create_dm_channel(ctx)
+                    .await
+                    .or_else(|e| {
+                        log::warn!("{}", &e);
+                        Err(e)
+                    })?;
+                response()
+                    .with_dm_channel(dm_chan)
+                    .send()
+                    .await?;
+            }
+        },
+        RelayOutcome::Relayed(RelayTarget::Channel(channel)) => {
+            response()
+                .with_channel(channel)
+                .send()
+                .await?;
+        },
+        RelayOutcome::Blocked(foul, None) => {
+            log::info!("Blocked {}'s message: {}", by, foul);
+            ResponseOk::new(ctx, msg)
+                .with_react('🚫')
+                .with_content(format!("I didn't pass that on, {}", foul))
+                .send()
+                .await?;
+        },
+        RelayOutcome::Blocked(foul, Some(draw)) => {
+            log::info!("{} fouled: {}", by, foul);
+            ResponseOk::new(ctx, msg)
+                .with_react('🚫')
+                .with_content(format!("Foul! {}. That clue's gone back in the bowl", foul))
+                .send()
+                .await?;
+            send_clue(ctx, msg, draw).await?;
+        },
+    }
+    Ok(true)
+}
+
+/// In text mode, checks a typed guess and sends the performer the next clue if it's right
+async fn check_guess(ctx: &Context, msg: &Message, by: &Player) -> CommandResult {
+    let draw = Executor::new(ctx, msg)
+        .try_write_and_get(|g| g.check_guess(by, &msg.content))
+        .await;
+    if let Ok(Some(draw)) = draw {
+        log::info!("{} guessed right", by);
+        msg.react(ctx, '✅').await?;
+        send_clue(ctx, msg, draw).await?;
+    }
+    Ok(())
+}
+
+/// Anything that isn't a command might be the performer's description in relay mode, or a
+/// typed guess in text mode. Wrong guesses, and chatter from anyone else, are just ignored
+#[hook]
+async fn normal_message(ctx: &Context, msg: &Message) {
+    if msg.author.bot {
+        return;
+    }
+    let by: Player = (&msg.author).into();
+    if msg.is_private() {
+        match relay(ctx, msg, &by).await {
+            Ok(true) => return,
+            Ok(false) => (),
+            Err(e) => {
+                log::warn!("{}", e);
+                return;
+            },
+        }
+    }
+    if let Err(e) = check_guess(ctx, msg, &by).await {
+        log::warn!("{}", e);
+    }
+}
+
 #[help]
 async fn my_help(
     context: &Context,
@@ -328,12 +1279,31 @@ async fn my_help(
 #[commands(
     status, 
     reset, 
+    rematch,
+    host,
+    kick,
+    config,
     join, 
     add_clue, 
+    fill_bowl,
     start_game, 
     next_turn,
+    ready,
+    back,
+    add_time,
+    end_turn,
     start_turn,
     next_clue,
+    dispute,
+    undo,
+    clues,
+    mark_solved,
+    mark_unsolved,
+    queue,
+    move_turn,
+    swap_turns,
+    set_turn,
+    sub,
     next_round,
 )]
 struct Yeats;
@@ -354,15 +1324,27 @@ async fn main() {
     let token = std::env::var("DISCORD_TOKEN")
         .expect("Couldn't get discord token");
 
+    let host_role = std::env::var("YEATS_HOST_ROLE")
+        .ok()
+        .and_then(|r| r.parse::<u64>().ok())
+        .map(RoleId);
+
+    let seed = std::env::var("YEATS_SEED")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok());
+
     let framework = StandardFramework::new()
         .configure(|c| c.prefix("!")
                    .no_dm_prefix(true))
+        .normal_message(normal_message)
         .help(&MY_HELP)
         .group(&YEATS_GROUP);
 
     let mut client = ClientBuilder::new(token)
         .type_map(TypeMap::new())
-        .type_map_insert::<Game>(Game::new())
+        .type_map_insert::<Game>(Game::new()
+                                 .with_host_role(host_role)
+                                 .with_seed(seed))
         .event_handler(Handler)
         .framework(framework)
         .await
@@ -1,4 +1,8 @@
-use tokio::time::{Duration, sleep};
+use std::{
+    future::Future,
+    sync::Arc,
+};
+use tokio::time::Duration;
 use serenity::{
     client::Context,
     framework::standard::CommandResult,
@@ -13,6 +17,7 @@ use serenity::{
 use async_trait::async_trait;
 use crate::{
     error::Error,
+    clock::{Clock, TokioClock},
     game::game::Game,
 };
 
@@ -261,8 +266,9 @@ impl<'a> Respondable for ResponseOk<'a> {
                 }
             }?;
             if let Some(redact_after) = self.redact_after {
-                sleep(Duration::from_secs(redact_after)).await;
-                message.edit(self.context, |m| m.content("*REDACTED*"))
+                let context = self.context;
+                let clock = game_clock(context).await;
+                redact_later(&*clock, redact_after, |text| message.edit(context, move |m| m.content(text)))
                     .await?;
             }
         }
@@ -270,6 +276,61 @@ impl<'a> Respondable for ResponseOk<'a> {
     }
 }
 
+/// What a redacted message gets edited to say
+pub const REDACTED: &str = "*REDACTED*";
+
+/// Waits `after` seconds on `clock`, then has `redact` swap the message's text for `REDACTED`
+pub async fn redact_later<F, Fut>(clock: &dyn Clock, after: u64, redact: F) -> Fut::Output
+where
+    F: FnOnce(&'static str) -> Fut,
+    Fut: Future,
+{
+    clock.sleep(Duration::from_secs(after)).await;
+    redact(REDACTED).await
+}
+
+/// The most characters Discord lets one message have
+pub const MESSAGE_LIMIT: usize = 2000;
+
+/// Breaks up text that's too long for one message, between lines where it can. A line too
+/// long for a message of its own gets cut wherever it has to be
+pub fn split_message(text: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let mut part = String::new();
+    for line in text.lines() {
+        let chars = line.chars().collect::<Vec<_>>();
+        let pieces = match chars.len() {
+            0 => vec![String::new()],
+            _ => chars.chunks(MESSAGE_LIMIT)
+                .map(|piece| piece.iter().collect::<String>())
+                .collect(),
+        };
+        for piece in pieces {
+            if !part.is_empty() && part.chars().count() + 1 + piece.chars().count() > MESSAGE_LIMIT {
+                parts.push(std::mem::take(&mut part));
+            }
+            if !part.is_empty() {
+                part.push('\n');
+            }
+            part.push_str(&piece);
+        }
+    }
+    if !part.is_empty() || parts.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+/// The game's clock, or the real one if there's no game
+async fn game_clock(context: &Context) -> Arc<dyn Clock> {
+    context.data
+        .read()
+        .await
+        .get::<Game>()
+        .map(|g| g.clock.clone())
+        .unwrap_or_else(|| Arc::new(TokioClock::new()))
+}
+
 #[async_trait]
 impl<'a> Respondable for ResponseErr<'a> {
     async fn send(self) -> CommandResult {
@@ -340,3 +401,44 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::clock::ManualClock;
+
+    #[tokio::test]
+    async fn test_redact_later() {
+        let clock = Arc::new(ManualClock::new());
+        let message = Arc::new(Mutex::new("Time's up! you solved the following clues".to_string()));
+        let redaction = {
+            let (clock, message) = (clock.clone(), message.clone());
+            tokio::spawn(async move {
+                redact_later(&*clock, 20, |text| async move {
+                    *message.lock().unwrap() = text.to_string();
+                }).await
+            })
+        };
+        clock.advance(Duration::from_secs(19)).await;
+        assert_ne!(*message.lock().unwrap(), REDACTED);
+        clock.advance(Duration::from_secs(1)).await;
+        redaction.await.unwrap();
+        assert_eq!(*message.lock().unwrap(), REDACTED);
+    }
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("short\n\nand sweet"), vec!["short\n\nand sweet"]);
+
+        let lines = (0..300).map(|i| format!("`{}` clue number {}", i, i)).collect::<Vec<_>>();
+        let parts = split_message(&lines.join("\n"));
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|p| p.chars().count() <= MESSAGE_LIMIT));
+        assert_eq!(parts.join("\n"), lines.join("\n"));
+
+        let long = "é".repeat(MESSAGE_LIMIT + 1);
+        let parts = split_message(&long);
+        assert_eq!(parts.iter().map(|p| p.chars().count()).collect::<Vec<_>>(), vec![MESSAGE_LIMIT, 1]);
+    }
+}